@@ -0,0 +1,76 @@
+//! Compares sequential and parallel directory loading on synthetic transcripts.
+//!
+//! Run with `cargo bench --bench loading`.
+
+use claude_usage_monitor::DataLoader;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::fmt::Write;
+use std::path::Path;
+
+const PROJECTS: usize = 20;
+const SESSIONS_PER_PROJECT: usize = 50;
+const LINES_PER_SESSION: usize = 100;
+
+fn write_synthetic_history(root: &Path) {
+    for project in 0..PROJECTS {
+        let project_dir = root.join(format!("-home-user-project-{}", project));
+        std::fs::create_dir_all(&project_dir).unwrap();
+
+        for session in 0..SESSIONS_PER_PROJECT {
+            let mut content = String::new();
+            for line in 0..LINES_PER_SESSION {
+                let day = 1 + (project + session) % 28;
+                let hour = line % 24;
+                let minute = (session + line) % 60;
+                writeln!(
+                    content,
+                    r#"{{"timestamp": "2024-01-{:02}T{:02}:{:02}:00Z", "sessionId": "s-{}-{}", "cwd": "/home/user/project-{}", "requestId": "req_{}_{}_{}", "message": {{"id": "msg_{}_{}_{}", "model": "claude-sonnet-4-20250514", "usage": {{"input_tokens": {}, "output_tokens": {}, "cache_creation_input_tokens": 1200, "cache_read_input_tokens": 24000}}}}}}"#,
+                    day,
+                    hour,
+                    minute,
+                    project,
+                    session,
+                    project,
+                    project,
+                    session,
+                    line,
+                    project,
+                    session,
+                    line,
+                    10 + line,
+                    200 + line
+                )
+                .unwrap();
+            }
+            std::fs::write(project_dir.join(format!("{}.jsonl", session)), content).unwrap();
+        }
+    }
+}
+
+fn bench_directory_loading(c: &mut Criterion) {
+    let root = tempfile::tempdir().unwrap();
+    write_synthetic_history(root.path());
+
+    let mut group = c.benchmark_group("load_from_directory");
+    group.sample_size(10);
+
+    let mut worker_counts = vec![1, 2, 4];
+    let available = DataLoader::available_workers();
+    if !worker_counts.contains(&available) {
+        worker_counts.push(available);
+    }
+
+    for workers in worker_counts {
+        let loader = DataLoader::new().with_workers(workers);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{}_workers", workers)),
+            &loader,
+            |b, loader| b.iter(|| loader.load_from_directory(root.path()).unwrap()),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_directory_loading);
+criterion_main!(benches);
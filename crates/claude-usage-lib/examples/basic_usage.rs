@@ -1,6 +1,6 @@
+use claude_usage_monitor::discover_data_roots;
 use claude_usage_monitor::prelude::*;
 use std::env;
-use std::path::Path;
 
 fn format_number(n: u64) -> String {
     let s = n.to_string();
@@ -14,22 +14,6 @@ fn format_number(n: u64) -> String {
     result.chars().rev().collect()
 }
 
-fn discover_claude_data_paths() -> Vec<std::path::PathBuf> {
-    let standard_paths = ["~/.claude/projects", "~/.config/claude/projects"];
-
-    let mut discovered_paths = Vec::new();
-
-    for path_str in &standard_paths {
-        let path = shellexpand::tilde(path_str);
-        let path = Path::new(path.as_ref());
-        if path.exists() && path.is_dir() {
-            discovered_paths.push(path.to_path_buf());
-        }
-    }
-
-    discovered_paths
-}
-
 fn main() -> Result<()> {
     let mut monitor = UsageMonitor::new();
 
@@ -43,7 +27,7 @@ fn main() -> Result<()> {
     } else {
         // Auto-discover Claude data paths
         println!("Auto-discovering Claude usage data...");
-        let claude_paths = discover_claude_data_paths();
+        let claude_paths = discover_data_roots();
 
         if claude_paths.is_empty() {
             eprintln!("No Claude data directories found in standard locations:");
@@ -54,28 +38,15 @@ fn main() -> Result<()> {
             std::process::exit(1);
         }
 
-        let mut loaded_any = false;
         for claude_path in &claude_paths {
-            println!("Checking directory: {}", claude_path.display());
-            match monitor.load_directory(claude_path) {
-                Ok(_) => {
-                    if !monitor.is_empty() {
-                        loaded_any = true;
-                        println!("Successfully loaded data from: {}", claude_path.display());
-                        break;
-                    }
-                }
-                Err(e) => {
-                    eprintln!(
-                        "Warning: Failed to load from {}: {}",
-                        claude_path.display(),
-                        e
-                    );
-                }
-            }
+            println!("Loading directory: {}", claude_path.display());
         }
 
-        if !loaded_any {
+        if let Err(e) = monitor.load_roots(&claude_paths) {
+            eprintln!("Warning: Failed to load Claude directories: {}", e);
+        }
+
+        if monitor.is_empty() {
             eprintln!("No usage data found in any Claude directories.");
             eprintln!("Make sure you have Claude usage data in one of:");
             for path in &claude_paths {
@@ -86,11 +57,29 @@ fn main() -> Result<()> {
     }
 
     println!(
-        "Loaded {} entries across {} sessions",
+        "Loaded {} entries across {} sessions ({} duplicates skipped)",
         monitor.entry_count(),
-        monitor.session_count()
+        monitor.session_count(),
+        monitor.duplicate_count()
     );
 
+    let report = monitor.load_report();
+    println!(
+        "Read {} lines from {} files, skipped {}",
+        report.lines_read(),
+        report.files_scanned(),
+        report.total_skipped()
+    );
+    for (reason, count) in report.skipped_lines() {
+        println!("  {}: {}", reason.description(), count);
+    }
+    for model in report.unknown_models() {
+        println!("  Unknown model (no pricing): {}", model);
+    }
+    for (path, error) in report.unreadable_files() {
+        println!("  Unreadable: {}: {}", path.display(), error);
+    }
+
     if monitor.is_empty() {
         println!("No usage data found.");
         return Ok(());
@@ -164,6 +153,21 @@ fn main() -> Result<()> {
         println!("{}: {} tokens, ${:.6}", model, tokens, cost);
     }
 
+    println!("\n--- Project Breakdown ---");
+    let mut projects: Vec<_> = monitor.get_project_breakdown().into_iter().collect();
+    projects.sort_by(|a, b| b.1.cost_usd().total_cmp(&a.1.cost_usd()));
+    for (project, usage) in projects {
+        println!(
+            "{}: {} requests, {} tokens ({:.0} weighted), ${:.6}, last active {}",
+            project,
+            usage.request_count(),
+            usage.token_counts().total_tokens(),
+            usage.weighted_tokens(),
+            usage.cost_usd(),
+            usage.last_activity().format("%Y-%m-%d %H:%M UTC")
+        );
+    }
+
     println!("\n--- Supported Models ---");
     let models = monitor.get_supported_models();
     for model in models {
@@ -191,13 +195,13 @@ fn main() -> Result<()> {
     println!();
 
     for plan in plans {
-        let percentage = monitor.get_plan_usage_percentage(plan);
+        let percentage = monitor.get_plan_usage_percentage(&plan);
         let max_tokens = plan.max_tokens();
 
         println!("{}:", plan.description());
 
         if current_tokens < max_tokens {
-            if let Some(time_to_limit) = monitor.estimate_time_to_plan_limit(plan) {
+            if let Some(time_to_limit) = monitor.estimate_time_to_plan_limit(&plan) {
                 let hours = time_to_limit.num_hours();
                 let minutes = time_to_limit.num_minutes() % 60;
                 let days = hours / 24;
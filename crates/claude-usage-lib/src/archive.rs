@@ -0,0 +1,220 @@
+use crate::data_structures::UsageEntry;
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Append-only copy of every usage entry seen, so history survives Claude
+/// Code deleting old transcripts. Stored as one JSON entry per line.
+pub struct UsageArchive {
+    path: PathBuf,
+    entries: Vec<UsageEntry>,
+    keys: HashSet<String>,
+}
+
+impl UsageArchive {
+    /// Opens the archive at `path`, creating it on first write. Lines that
+    /// cannot be parsed are ignored.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut entries = Vec::new();
+        let mut keys = HashSet::new();
+
+        if path.exists() {
+            let file = File::open(&path)
+                .with_context(|| format!("Failed to open archive: {}", path.display()))?;
+
+            for line in BufReader::new(file).lines() {
+                let line = line.context("Failed to read archive line")?;
+                if let Ok(entry) = serde_json::from_str::<UsageEntry>(&line) {
+                    if keys.insert(archive_key(&entry)) {
+                        entries.push(entry);
+                    }
+                }
+            }
+        }
+
+        entries.sort_by_key(|entry| entry.timestamp());
+
+        Ok(Self {
+            path,
+            entries,
+            keys,
+        })
+    }
+
+    /// `$XDG_DATA_HOME/claude-usage/archive.jsonl`, falling back to
+    /// `~/.local/share/claude-usage/archive.jsonl`.
+    pub fn default_path() -> Option<PathBuf> {
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| {
+                std::env::var_os("HOME")
+                    .map(|home| PathBuf::from(home).join(".local").join("share"))
+            })?;
+
+        Some(data_home.join("claude-usage").join("archive.jsonl"))
+    }
+
+    /// Appends the entries that are not archived yet and returns them.
+    pub fn record(&mut self, entries: &[UsageEntry]) -> Result<Vec<UsageEntry>> {
+        let new_entries: Vec<UsageEntry> = entries
+            .iter()
+            .filter(|entry| self.keys.insert(archive_key(entry)))
+            .cloned()
+            .collect();
+
+        if new_entries.is_empty() {
+            return Ok(new_entries);
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create archive directory: {}", parent.display())
+            })?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open archive: {}", self.path.display()))?;
+        let mut writer = BufWriter::new(file);
+
+        for entry in &new_entries {
+            serde_json::to_writer(&mut writer, entry)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush().context("Failed to write archive")?;
+
+        self.entries.extend(new_entries.iter().cloned());
+        self.entries.sort_by_key(|entry| entry.timestamp());
+
+        Ok(new_entries)
+    }
+
+    /// The `live` entries plus the archived entries that were loaded from
+    /// under `roots` but are no longer in the live logs. Archived entries
+    /// from other roots are left out, so a scoped load stays scoped.
+    pub fn history_for<P: AsRef<Path>>(&self, roots: &[P], live: &[UsageEntry]) -> Vec<UsageEntry> {
+        let roots: Vec<PathBuf> = roots
+            .iter()
+            .map(|root| {
+                let root = root.as_ref();
+                root.canonicalize().unwrap_or_else(|_| root.to_path_buf())
+            })
+            .collect();
+        let live_keys: HashSet<String> = live.iter().map(archive_key).collect();
+
+        let mut entries = live.to_vec();
+        entries.extend(
+            self.entries
+                .iter()
+                .filter(|entry| {
+                    is_within(entry, &roots) && !live_keys.contains(&archive_key(entry))
+                })
+                .cloned(),
+        );
+        entries.sort_by_key(|entry| entry.timestamp());
+        entries
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn entries(&self) -> &[UsageEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Whether `entry` was loaded from under one of `roots`. Entries archived
+/// without their transcript path fall back to the data root they came from.
+fn is_within(entry: &UsageEntry, roots: &[PathBuf]) -> bool {
+    match (entry.source_file(), entry.source_root()) {
+        (Some(file), _) => roots.iter().any(|root| file.starts_with(root)),
+        (None, Some(source_root)) => roots.iter().any(|root| {
+            let source_root = source_root
+                .canonicalize()
+                .unwrap_or_else(|_| source_root.to_path_buf());
+            source_root.starts_with(root)
+        }),
+        (None, None) => false,
+    }
+}
+
+/// Identifies an entry in the archive: its message/request id pair when
+/// available, otherwise its timestamp, model and token counts.
+fn archive_key(entry: &UsageEntry) -> String {
+    entry.dedup_key().unwrap_or_else(|| {
+        format!(
+            "{}|{}|{}|{}|{}|{}",
+            entry.timestamp().to_rfc3339(),
+            entry.model(),
+            entry.input_tokens(),
+            entry.output_tokens(),
+            entry.cache_creation_input_tokens(),
+            entry.cache_read_input_tokens()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn entry(minute: u32, message_id: Option<&str>) -> UsageEntry {
+        UsageEntry::new(
+            Utc.with_ymd_and_hms(2024, 1, 1, 12, minute, 0).unwrap(),
+            "claude-3-sonnet-20240229".to_string(),
+            100,
+            50,
+            0,
+            0,
+            0.001,
+        )
+        .with_ids(
+            message_id.map(str::to_string),
+            message_id.map(|id| format!("req_{}", id)),
+        )
+    }
+
+    #[test]
+    fn test_record_only_appends_new_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("archive.jsonl");
+
+        let mut archive = UsageArchive::open(&path).unwrap();
+        assert!(archive.is_empty());
+
+        let added = archive
+            .record(&[entry(0, Some("msg_1")), entry(1, None)])
+            .unwrap();
+        assert_eq!(added.len(), 2);
+
+        let added = archive
+            .record(&[
+                entry(0, Some("msg_1")),
+                entry(1, None),
+                entry(2, Some("msg_2")),
+            ])
+            .unwrap();
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].message_id(), Some("msg_2"));
+
+        let reopened = UsageArchive::open(&path).unwrap();
+        assert_eq!(reopened.len(), 3);
+        assert_eq!(reopened.entries()[0].message_id(), Some("msg_1"));
+    }
+}
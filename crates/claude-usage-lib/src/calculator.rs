@@ -1,4 +1,7 @@
-use crate::data_structures::{BurnRate, SessionBlock, UsageEntry, UsageProjection};
+use crate::cost::Usd;
+use crate::data_structures::{
+    BurnRate, LimitEstimate, LimitSource, SessionBlock, UsageEntry, UsageProjection,
+};
 use chrono::{DateTime, Duration, Utc};
 
 pub struct Calculator;
@@ -9,12 +12,20 @@ impl Calculator {
     }
 
     pub fn calculate_burn_rate(&self, block: &SessionBlock) -> Option<BurnRate> {
+        self.calculate_block_unit_burn_rate(block, block.token_counts().total_tokens() as f64)
+    }
+
+    /// Burn rate of a block that used `units` quota units over its duration.
+    pub fn calculate_block_unit_burn_rate(
+        &self,
+        block: &SessionBlock,
+        units: f64,
+    ) -> Option<BurnRate> {
         if block.is_empty() || block.duration_minutes() == 0.0 {
             return None;
         }
 
-        let total_tokens = block.token_counts().total_tokens() as f64;
-        let tokens_per_minute = total_tokens / block.duration_minutes();
+        let tokens_per_minute = units / block.duration_minutes();
         let cost_per_hour = (block.cost_usd() / block.duration_minutes()) * 60.0;
 
         Some(BurnRate::new(tokens_per_minute, cost_per_hour))
@@ -41,6 +52,21 @@ impl Calculator {
         block: &SessionBlock,
         pricing_provider: &crate::pricing::PricingProvider,
         current_time: DateTime<Utc>,
+    ) -> Option<BurnRate> {
+        self.calculate_unit_burn_rate(
+            block,
+            block.calculate_weighted_tokens(pricing_provider),
+            current_time,
+        )
+    }
+
+    /// Burn rate of a block that has used `units` quota units so far,
+    /// measured from the block start to `current_time`.
+    pub fn calculate_unit_burn_rate(
+        &self,
+        block: &SessionBlock,
+        units: f64,
+        current_time: DateTime<Utc>,
     ) -> Option<BurnRate> {
         if block.is_empty() {
             return None;
@@ -51,8 +77,7 @@ impl Calculator {
             return None;
         }
 
-        let weighted_tokens = block.calculate_weighted_tokens(pricing_provider);
-        let tokens_per_minute = weighted_tokens / duration_minutes;
+        let tokens_per_minute = units / duration_minutes;
         let cost_per_hour = (block.cost_usd() / duration_minutes) * 60.0;
 
         Some(BurnRate::new(tokens_per_minute, cost_per_hour))
@@ -69,7 +94,7 @@ impl Calculator {
 
         let burn_rate = self.calculate_burn_rate(block)?;
         let current_tokens = block.token_counts().total_tokens();
-        let current_cost = block.cost_usd();
+        let current_cost = block.cost();
 
         let remaining_duration = block.end_time() - current_time;
         let remaining_minutes = remaining_duration.num_seconds() as f64 / 60.0;
@@ -77,7 +102,7 @@ impl Calculator {
 
         let projected_additional_tokens =
             (burn_rate.tokens_per_minute() * remaining_minutes) as u64;
-        let projected_additional_cost = burn_rate.cost_per_hour() * remaining_hours;
+        let projected_additional_cost = Usd::from_f64(burn_rate.cost_per_hour() * remaining_hours);
 
         Some(UsageProjection::new(
             current_tokens,
@@ -132,8 +157,24 @@ impl Calculator {
         current_time: DateTime<Utc>,
         pricing_provider: &crate::pricing::PricingProvider,
     ) -> f64 {
+        self.calculate_hourly_unit_rate(blocks, current_time, |block| {
+            block.calculate_weighted_tokens(pricing_provider)
+        })
+    }
+
+    /// Units per minute over the last hour, where `block_units` gives the
+    /// units a whole block used.
+    pub fn calculate_hourly_unit_rate<F>(
+        &self,
+        blocks: &[SessionBlock],
+        current_time: DateTime<Utc>,
+        block_units: F,
+    ) -> f64
+    where
+        F: Fn(&SessionBlock) -> f64,
+    {
         let one_hour_ago = current_time - Duration::hours(1);
-        let mut total_weighted_tokens = 0.0;
+        let mut total_units = 0.0;
 
         for block in blocks {
             if block.is_empty() {
@@ -152,26 +193,25 @@ impl Calculator {
             let overlap_duration = (overlap_end - overlap_start).num_seconds() as f64 / 60.0;
 
             if overlap_duration > 0.0 {
-                let session_weighted_tokens = block.calculate_weighted_tokens(pricing_provider);
+                let session_units = block_units(block);
                 let total_session_duration = block.duration_minutes();
 
                 if total_session_duration > 0.0 {
-                    let weighted_tokens_in_hour =
-                        session_weighted_tokens * (overlap_duration / total_session_duration);
-                    total_weighted_tokens += weighted_tokens_in_hour;
+                    let units_in_hour = session_units * (overlap_duration / total_session_duration);
+                    total_units += units_in_hour;
                 }
             }
         }
 
-        total_weighted_tokens / 60.0
+        total_units / 60.0
     }
 
     pub fn calculate_weighted_tokens(&self, entry: &UsageEntry, model_weight: f64) -> f64 {
         (entry.total_tokens() as f64) * model_weight
     }
 
-    pub fn calculate_total_cost(&self, blocks: &[SessionBlock]) -> f64 {
-        blocks.iter().map(|block| block.cost_usd()).sum()
+    pub fn calculate_total_cost(&self, blocks: &[SessionBlock]) -> Usd {
+        blocks.iter().map(|block| block.cost()).sum()
     }
 
     pub fn calculate_total_tokens(&self, blocks: &[SessionBlock]) -> u64 {
@@ -182,13 +222,28 @@ impl Calculator {
     }
 
     pub fn calculate_average_burn_rate(&self, blocks: &[SessionBlock]) -> Option<BurnRate> {
+        self.calculate_average_unit_burn_rate(blocks, |block| {
+            block.token_counts().total_tokens() as f64
+        })
+    }
+
+    /// Average burn rate over `blocks`, where `block_units` gives the units a
+    /// whole block used.
+    pub fn calculate_average_unit_burn_rate<F>(
+        &self,
+        blocks: &[SessionBlock],
+        block_units: F,
+    ) -> Option<BurnRate>
+    where
+        F: Fn(&SessionBlock) -> f64,
+    {
         if blocks.is_empty() {
             return None;
         }
 
         let burn_rates: Vec<BurnRate> = blocks
             .iter()
-            .filter_map(|block| self.calculate_burn_rate(block))
+            .filter_map(|block| self.calculate_block_unit_burn_rate(block, block_units(block)))
             .collect();
 
         if burn_rates.is_empty() {
@@ -208,9 +263,24 @@ impl Calculator {
     }
 
     pub fn calculate_peak_burn_rate(&self, blocks: &[SessionBlock]) -> Option<BurnRate> {
+        self.calculate_peak_unit_burn_rate(blocks, |block| {
+            block.token_counts().total_tokens() as f64
+        })
+    }
+
+    /// Highest block burn rate, where `block_units` gives the units a whole
+    /// block used.
+    pub fn calculate_peak_unit_burn_rate<F>(
+        &self,
+        blocks: &[SessionBlock],
+        block_units: F,
+    ) -> Option<BurnRate>
+    where
+        F: Fn(&SessionBlock) -> f64,
+    {
         blocks
             .iter()
-            .filter_map(|block| self.calculate_burn_rate(block))
+            .filter_map(|block| self.calculate_block_unit_burn_rate(block, block_units(block)))
             .max_by(|a, b| {
                 a.tokens_per_minute()
                     .partial_cmp(&b.tokens_per_minute())
@@ -233,6 +303,58 @@ impl Calculator {
 
         Some(Duration::minutes(minutes_to_limit as i64))
     }
+
+    /// When `used` units reach `budget` at the average rate since
+    /// `window_start`, or `None` if that is not before `resets_at` (or the
+    /// budget is already spent).
+    pub fn project_exhaustion(
+        &self,
+        used: f64,
+        budget: f64,
+        window_start: DateTime<Utc>,
+        resets_at: DateTime<Utc>,
+        current_time: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let elapsed_seconds = (current_time - window_start).num_seconds() as f64;
+        let remaining = budget - used;
+        if elapsed_seconds <= 0.0 || used <= 0.0 || remaining <= 0.0 {
+            return None;
+        }
+
+        let seconds_to_limit = remaining / used * elapsed_seconds;
+        let exhaustion = current_time + Duration::seconds(seconds_to_limit.round() as i64);
+        (exhaustion < resets_at).then_some(exhaustion)
+    }
+
+    /// Estimates the per-block limit as the (nearest-rank) P90 of the usage
+    /// in blocks that hit the limit, or the largest block if none did.
+    pub fn estimate_token_limit(
+        &self,
+        limit_hit_units: &[f64],
+        block_units: &[f64],
+    ) -> Option<LimitEstimate> {
+        let mut hits: Vec<f64> = limit_hit_units
+            .iter()
+            .copied()
+            .filter(|units| *units > 0.0)
+            .collect();
+
+        if !hits.is_empty() {
+            hits.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let rank = (hits.len() as f64 * 0.9).ceil() as usize;
+            return Some(LimitEstimate::new(
+                hits[rank.max(1) - 1],
+                LimitSource::LimitEvents { blocks: hits.len() },
+            ));
+        }
+
+        block_units
+            .iter()
+            .copied()
+            .filter(|units| *units > 0.0)
+            .max_by(|a, b| a.partial_cmp(b).unwrap())
+            .map(|peak| LimitEstimate::new(peak, LimitSource::PeakBlock))
+    }
 }
 
 impl Default for Calculator {
@@ -356,4 +478,22 @@ mod tests {
         let time_to_limit = calculator.calculate_time_to_limit(1000, 500, 5.0);
         assert!(time_to_limit.is_none());
     }
+
+    #[test]
+    fn test_estimate_token_limit() {
+        let calculator = Calculator::new();
+
+        let hits: Vec<f64> = (1..=10).map(|n| n as f64 * 1000.0).collect();
+        let estimate = calculator.estimate_token_limit(&hits, &[50_000.0]).unwrap();
+        assert_eq!(estimate.limit(), 9000.0);
+        assert_eq!(estimate.source(), LimitSource::LimitEvents { blocks: 10 });
+
+        let estimate = calculator
+            .estimate_token_limit(&[], &[1200.0, 0.0, 3400.0])
+            .unwrap();
+        assert_eq!(estimate.limit(), 3400.0);
+        assert_eq!(estimate.source(), LimitSource::PeakBlock);
+
+        assert!(calculator.estimate_token_limit(&[], &[0.0]).is_none());
+    }
 }
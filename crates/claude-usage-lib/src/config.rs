@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+
+/// `claude-usage` in `$XDG_CONFIG_HOME`, falling back to `~/.config`.
+pub fn config_dir() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_home.join("claude-usage"))
+}
+
+/// The first of `names` that exists in [`config_dir`].
+pub(crate) fn find_config_file(names: &[&str]) -> Option<PathBuf> {
+    let dir = config_dir()?;
+    names
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
@@ -0,0 +1,181 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub};
+use std::str::FromStr;
+
+const MICROS_PER_DOLLAR: f64 = 1_000_000.0;
+
+/// An amount in US dollars, stored as whole micro-dollars so that sums of
+/// many entries are exact. Serialized as a plain dollar number.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Usd(i64);
+
+impl Usd {
+    pub const ZERO: Usd = Usd(0);
+
+    pub fn from_micros(micros: i64) -> Self {
+        Self(micros)
+    }
+
+    /// Rounds `dollars` to the nearest micro-dollar.
+    pub fn from_f64(dollars: f64) -> Self {
+        Self((dollars * MICROS_PER_DOLLAR).round() as i64)
+    }
+
+    pub fn micros(&self) -> i64 {
+        self.0
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64 / MICROS_PER_DOLLAR
+    }
+
+    pub fn abs_diff(&self, other: Usd) -> Usd {
+        Usd((self.0 - other.0).abs())
+    }
+}
+
+impl Add for Usd {
+    type Output = Usd;
+
+    fn add(self, other: Usd) -> Usd {
+        Usd(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Usd {
+    fn add_assign(&mut self, other: Usd) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Usd {
+    type Output = Usd;
+
+    fn sub(self, other: Usd) -> Usd {
+        Usd(self.0 - other.0)
+    }
+}
+
+impl Sum for Usd {
+    fn sum<I: Iterator<Item = Usd>>(iter: I) -> Usd {
+        iter.fold(Usd::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Usd> for Usd {
+    fn sum<I: Iterator<Item = &'a Usd>>(iter: I) -> Usd {
+        iter.copied().sum()
+    }
+}
+
+impl fmt::Display for Usd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let micros = self.0.unsigned_abs();
+        write!(
+            f,
+            "{}${}.{:06}",
+            sign,
+            micros / 1_000_000,
+            micros % 1_000_000
+        )
+    }
+}
+
+impl Serialize for Usd {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.as_f64())
+    }
+}
+
+impl<'de> Deserialize<'de> for Usd {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f64::deserialize(deserializer).map(Usd::from_f64)
+    }
+}
+
+/// Where an entry's `cost_usd` comes from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CostMode {
+    /// The logged `cost_usd` when present, otherwise computed from tokens.
+    #[default]
+    Auto,
+    /// Always computed from tokens with the pricing table.
+    Calculate,
+    /// Only the logged `cost_usd`; entries without one cost nothing.
+    Display,
+    /// Like `Auto`, but keeps both values and reports entries where they
+    /// disagree.
+    Audit,
+}
+
+impl CostMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CostMode::Auto => "auto",
+            CostMode::Calculate => "calculate",
+            CostMode::Display => "display",
+            CostMode::Audit => "audit",
+        }
+    }
+}
+
+impl fmt::Display for CostMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for CostMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(CostMode::Auto),
+            "calculate" => Ok(CostMode::Calculate),
+            "display" => Ok(CostMode::Display),
+            "audit" => Ok(CostMode::Audit),
+            _ => Err(anyhow::anyhow!(
+                "Unknown cost mode '{}' (expected auto, calculate, display or audit)",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usd_sums_exactly() {
+        let total: Usd = std::iter::repeat_n(Usd::from_f64(0.000_1), 10_000).sum();
+        assert_eq!(total, Usd::from_f64(1.0));
+        assert_eq!(total.as_f64(), 1.0);
+
+        let float_total: f64 = std::iter::repeat_n(0.000_1, 10_000).sum();
+        assert_ne!(float_total, 1.0);
+    }
+
+    #[test]
+    fn test_usd_display_and_serde() {
+        assert_eq!(Usd::from_f64(1.5).to_string(), "$1.500000");
+        assert_eq!(Usd::from_micros(-25).to_string(), "-$0.000025");
+
+        let json = serde_json::to_string(&Usd::from_f64(0.25)).unwrap();
+        assert_eq!(json, "0.25");
+        assert_eq!(serde_json::from_str::<Usd>("0.000002").unwrap().micros(), 2);
+    }
+
+    #[test]
+    fn test_parse_cost_mode() {
+        assert_eq!("audit".parse::<CostMode>().unwrap(), CostMode::Audit);
+        assert_eq!(
+            "Calculate".parse::<CostMode>().unwrap(),
+            CostMode::Calculate
+        );
+        assert!("invoice".parse::<CostMode>().is_err());
+    }
+}
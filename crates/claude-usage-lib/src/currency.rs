@@ -0,0 +1,231 @@
+use crate::config::find_config_file;
+use crate::cost::Usd;
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// A currency to report costs in, with dated exchange rates from USD.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Currency {
+    code: String,
+    symbol: String,
+    /// Units of this currency per US dollar, keyed by the date each rate
+    /// takes effect.
+    rates: BTreeMap<NaiveDate, f64>,
+}
+
+impl Currency {
+    pub fn new(code: &str, symbol: &str) -> Self {
+        Self {
+            code: code.to_uppercase(),
+            symbol: symbol.to_string(),
+            rates: BTreeMap::new(),
+        }
+    }
+
+    pub fn usd() -> Self {
+        Self::new("USD", "$").with_rate(NaiveDate::MIN, 1.0)
+    }
+
+    pub fn with_rate(mut self, date: NaiveDate, rate: f64) -> Self {
+        self.rates.insert(date, rate);
+        self
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn is_usd(&self) -> bool {
+        self.code == "USD"
+    }
+
+    pub fn rates(&self) -> &BTreeMap<NaiveDate, f64> {
+        &self.rates
+    }
+
+    /// The latest rate on or before `date`. Dates before the first known
+    /// rate use the first rate; `None` only if the currency has no rates.
+    pub fn rate_at(&self, date: NaiveDate) -> Option<f64> {
+        self.rates
+            .range(..=date)
+            .next_back()
+            .or_else(|| self.rates.iter().next())
+            .map(|(_, rate)| *rate)
+    }
+
+    /// Converts `amount` at the rate in force at `at`.
+    pub fn convert(&self, amount: Usd, at: DateTime<Utc>) -> Option<f64> {
+        self.rate_at(at.date_naive())
+            .map(|rate| amount.as_f64() * rate)
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Self::usd()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RatesFile {
+    #[serde(default)]
+    currencies: HashMap<String, CurrencyRates>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CurrencyRates {
+    symbol: Option<String>,
+    /// `YYYY-MM-DD` to units per USD.
+    rates: BTreeMap<String, f64>,
+}
+
+/// Exchange rates read from a local file; nothing is fetched over the
+/// network.
+///
+/// ```toml
+/// [currencies.EUR]
+/// symbol = "€"
+/// rates = { "2025-01-01" = 0.96, "2025-06-01" = 0.88 }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeRates {
+    currencies: HashMap<String, Currency>,
+}
+
+impl ExchangeRates {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read exchange rates: {}", path.display()))?;
+
+        let file: RatesFile = if path.extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str(&content)
+                .with_context(|| format!("Invalid exchange rates: {}", path.display()))?
+        } else {
+            serde_json::from_str(&content)
+                .with_context(|| format!("Invalid exchange rates: {}", path.display()))?
+        };
+
+        let mut rates = Self::new();
+        for (code, entry) in file.currencies {
+            if entry.rates.is_empty() {
+                return Err(anyhow::anyhow!("No exchange rates given for {}", code));
+            }
+
+            let code = code.to_uppercase();
+            let symbol = entry.symbol.unwrap_or_else(|| format!("{} ", code));
+            let mut currency = Currency::new(&code, &symbol);
+            for (date, rate) in entry.rates {
+                let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d").with_context(|| {
+                    format!("Invalid date '{}' in {} exchange rates", date, code)
+                })?;
+                if !(rate.is_finite() && rate > 0.0) {
+                    return Err(anyhow::anyhow!(
+                        "Invalid {} exchange rate on {}: {}",
+                        code,
+                        date,
+                        rate
+                    ));
+                }
+                currency = currency.with_rate(date, rate);
+            }
+            rates.add_currency(currency);
+        }
+
+        Ok(rates)
+    }
+
+    /// `rates.toml` or `rates.json` in [`crate::config_dir`], whichever
+    /// exists.
+    pub fn default_config_path() -> Option<PathBuf> {
+        find_config_file(&["rates.toml", "rates.json"])
+    }
+
+    pub fn add_currency(&mut self, currency: Currency) {
+        self.currencies
+            .insert(currency.code().to_string(), currency);
+    }
+
+    pub fn get(&self, code: &str) -> Option<&Currency> {
+        self.currencies.get(&code.to_uppercase())
+    }
+
+    /// Looks up `code`, case-insensitively. USD is always available.
+    pub fn currency(&self, code: &str) -> Result<Currency> {
+        match self.get(code) {
+            Some(currency) => Ok(currency.clone()),
+            None if code.eq_ignore_ascii_case("USD") => Ok(Currency::usd()),
+            None => Err(anyhow::anyhow!("No exchange rates for currency '{}'", code)),
+        }
+    }
+
+    pub fn codes(&self) -> Vec<&str> {
+        let mut codes: Vec<&str> = self.currencies.keys().map(String::as_str).collect();
+        codes.sort();
+        codes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::io::Write;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_rate_at_uses_latest_rate_in_force() {
+        let eur = Currency::new("eur", "€")
+            .with_rate(date(2025, 1, 1), 0.96)
+            .with_rate(date(2025, 6, 1), 0.88);
+
+        assert_eq!(eur.code(), "EUR");
+        assert_eq!(eur.rate_at(date(2024, 6, 1)), Some(0.96));
+        assert_eq!(eur.rate_at(date(2025, 5, 31)), Some(0.96));
+        assert_eq!(eur.rate_at(date(2025, 6, 1)), Some(0.88));
+        assert_eq!(Currency::new("CHF", "CHF ").rate_at(date(2025, 1, 1)), None);
+
+        let at = Utc.with_ymd_and_hms(2025, 7, 1, 12, 0, 0).unwrap();
+        let converted = eur.convert(Usd::from_f64(10.0), at).unwrap();
+        assert!((converted - 8.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_exchange_rates_from_toml() {
+        let mut file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+        writeln!(
+            file,
+            r#"
+[currencies.EUR]
+symbol = "€"
+rates = {{ "2025-01-01" = 0.96 }}
+
+[currencies.chf]
+rates = {{ "2025-01-01" = 0.90, "2025-03-01" = 0.88 }}
+"#
+        )
+        .unwrap();
+
+        let rates = ExchangeRates::from_file(file.path()).unwrap();
+        assert_eq!(rates.codes(), vec!["CHF", "EUR"]);
+        assert_eq!(rates.currency("eur").unwrap().symbol(), "€");
+        assert_eq!(rates.currency("CHF").unwrap().symbol(), "CHF ");
+        assert_eq!(rates.currency("CHF").unwrap().rates().len(), 2);
+        assert!(rates.currency("usd").unwrap().is_usd());
+        assert!(rates.currency("GBP").is_err());
+    }
+}
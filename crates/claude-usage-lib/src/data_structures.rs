@@ -1,39 +1,122 @@
+use crate::cost::Usd;
+use crate::limits::LimitEvent;
+use crate::plans::CustomPlan;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ClaudePlan {
     Pro,
     Max5,
     Max20,
+    /// Limit estimated from past blocks; see `UsageMonitor::estimate_token_limit`.
+    Auto,
+    /// A plan from the plan registry.
+    Custom(CustomPlan),
 }
 
 impl ClaudePlan {
+    /// The fixed per-block limit. `Auto` falls back to the Pro limit until
+    /// there is history to estimate from.
     pub fn max_tokens(&self) -> u64 {
         match self {
-            ClaudePlan::Pro => 44_000,
+            ClaudePlan::Pro | ClaudePlan::Auto => 44_000,
             ClaudePlan::Max5 => 220_000,
             ClaudePlan::Max20 => 880_000,
+            ClaudePlan::Custom(plan) => plan.token_limit(),
         }
     }
 
-    pub fn name(&self) -> &'static str {
+    pub fn name(&self) -> &str {
         match self {
             ClaudePlan::Pro => "Claude Pro",
             ClaudePlan::Max5 => "Claude Max 5",
             ClaudePlan::Max20 => "Claude Max 20",
+            ClaudePlan::Auto => "Auto",
+            ClaudePlan::Custom(plan) => plan.name(),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            ClaudePlan::Pro => "Claude Pro (~44K tokens/day)".to_string(),
+            ClaudePlan::Max5 => "Claude Max 5 (~220K tokens/day)".to_string(),
+            ClaudePlan::Max20 => "Claude Max 20 (~880K tokens/day)".to_string(),
+            ClaudePlan::Auto => "Auto (limit estimated from past blocks)".to_string(),
+            ClaudePlan::Custom(plan) => {
+                format!("{} ({} tokens/block)", plan.name(), plan.token_limit())
+            }
         }
     }
 
-    pub fn description(&self) -> &'static str {
+    /// Weekly all-models budget the plan brings, if any.
+    pub fn weekly_limit(&self) -> Option<f64> {
         match self {
-            ClaudePlan::Pro => "Claude Pro (~44K tokens/day)",
-            ClaudePlan::Max5 => "Claude Max 5 (~220K tokens/day)",
-            ClaudePlan::Max20 => "Claude Max 20 (~880K tokens/day)",
+            ClaudePlan::Custom(plan) => plan.weekly_limit(),
+            _ => None,
+        }
+    }
+
+    /// Spending cap per block the plan brings, if any.
+    pub fn cost_cap(&self) -> Option<Usd> {
+        match self {
+            ClaudePlan::Custom(plan) => plan.cost_cap(),
+            _ => None,
         }
     }
 }
 
+impl std::str::FromStr for ClaudePlan {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pro" => Ok(ClaudePlan::Pro),
+            "max5" => Ok(ClaudePlan::Max5),
+            "max20" => Ok(ClaudePlan::Max20),
+            "auto" => Ok(ClaudePlan::Auto),
+            "custom" => Err(anyhow::anyhow!(
+                "Custom plans are selected by name: define one under [plans.<name>] in plans.toml and pass --plan <name>"
+            )),
+            _ => Err(anyhow::anyhow!(
+                "Unknown plan '{}' (expected pro, max5, max20 or auto)",
+                s
+            )),
+        }
+    }
+}
+
+/// Where an estimated per-block limit came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitSource {
+    /// P90 of the usage in blocks that hit the limit.
+    LimitEvents { blocks: usize },
+    /// The largest block seen, when no block has hit the limit yet.
+    PeakBlock,
+}
+
+/// A per-block limit, in limit-model units, estimated from history.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LimitEstimate {
+    limit: f64,
+    source: LimitSource,
+}
+
+impl LimitEstimate {
+    pub fn new(limit: f64, source: LimitSource) -> Self {
+        Self { limit, source }
+    }
+
+    pub fn limit(&self) -> f64 {
+        self.limit
+    }
+
+    pub fn source(&self) -> LimitSource {
+        self.source
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsageEntry {
     timestamp: DateTime<Utc>,
@@ -42,7 +125,41 @@ pub struct UsageEntry {
     output_tokens: u64,
     cache_creation_input_tokens: u64,
     cache_read_input_tokens: u64,
-    cost_usd: f64,
+    cost_usd: Usd,
+    /// Part of `cache_creation_input_tokens` written to the 1-hour cache.
+    #[serde(default)]
+    cache_creation_1h_input_tokens: u64,
+    /// Logged and computed costs, kept when loading in audit mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    logged_cost_usd: Option<Usd>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    calculated_cost_usd: Option<Usd>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    message_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    git_branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    is_sidechain: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    user_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_root: Option<PathBuf>,
+    /// Transcript the entry was read from, canonicalized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_file: Option<PathBuf>,
+    /// Pricing profile (e.g. `bedrock`) the entry was priced with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pricing_profile: Option<String>,
 }
 
 impl UsageEntry {
@@ -62,10 +179,91 @@ impl UsageEntry {
             output_tokens,
             cache_creation_input_tokens,
             cache_read_input_tokens,
-            cost_usd,
+            cost_usd: Usd::from_f64(cost_usd),
+            cache_creation_1h_input_tokens: 0,
+            logged_cost_usd: None,
+            calculated_cost_usd: None,
+            message_id: None,
+            request_id: None,
+            session_id: None,
+            project: None,
+            cwd: None,
+            git_branch: None,
+            version: None,
+            is_sidechain: None,
+            user_type: None,
+            source_root: None,
+            source_file: None,
+            pricing_profile: None,
         }
     }
 
+    /// Sets how many of the cache-write tokens went to the 1-hour cache; the
+    /// rest are 5-minute writes.
+    pub fn with_cache_creation_1h_tokens(mut self, tokens: u64) -> Self {
+        self.cache_creation_1h_input_tokens = tokens.min(self.cache_creation_input_tokens);
+        self
+    }
+
+    pub fn with_ids(mut self, message_id: Option<String>, request_id: Option<String>) -> Self {
+        self.message_id = message_id;
+        self.request_id = request_id;
+        self
+    }
+
+    pub fn with_session_id(mut self, session_id: Option<String>) -> Self {
+        self.session_id = session_id;
+        self
+    }
+
+    /// Sets the project folder name, i.e. the directory under `projects/`
+    /// that held the transcript.
+    pub fn with_project(mut self, project: Option<String>) -> Self {
+        self.project = project;
+        self
+    }
+
+    pub fn with_cwd(mut self, cwd: Option<String>) -> Self {
+        self.cwd = cwd;
+        self
+    }
+
+    pub fn with_git_branch(mut self, git_branch: Option<String>) -> Self {
+        self.git_branch = git_branch;
+        self
+    }
+
+    pub fn with_version(mut self, version: Option<String>) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn with_is_sidechain(mut self, is_sidechain: Option<bool>) -> Self {
+        self.is_sidechain = is_sidechain;
+        self
+    }
+
+    pub fn with_user_type(mut self, user_type: Option<String>) -> Self {
+        self.user_type = user_type;
+        self
+    }
+
+    /// Sets the data root (e.g. `~/.claude/projects`) the entry was loaded from.
+    pub fn with_source_root(mut self, source_root: Option<PathBuf>) -> Self {
+        self.source_root = source_root;
+        self
+    }
+
+    pub fn with_source_file(mut self, source_file: Option<PathBuf>) -> Self {
+        self.source_file = source_file;
+        self
+    }
+
+    pub fn with_pricing_profile(mut self, pricing_profile: Option<String>) -> Self {
+        self.pricing_profile = pricing_profile;
+        self
+    }
+
     pub fn timestamp(&self) -> DateTime<Utc> {
         self.timestamp
     }
@@ -86,14 +284,118 @@ impl UsageEntry {
         self.cache_creation_input_tokens
     }
 
+    pub fn cache_creation_5m_input_tokens(&self) -> u64 {
+        self.cache_creation_input_tokens - self.cache_creation_1h_input_tokens
+    }
+
+    pub fn cache_creation_1h_input_tokens(&self) -> u64 {
+        self.cache_creation_1h_input_tokens
+    }
+
     pub fn cache_read_input_tokens(&self) -> u64 {
         self.cache_read_input_tokens
     }
 
-    pub fn cost_usd(&self) -> f64 {
+    pub fn with_cost(mut self, cost: Usd) -> Self {
+        self.cost_usd = cost;
+        self
+    }
+
+    pub fn with_cost_usd(self, cost_usd: f64) -> Self {
+        self.with_cost(Usd::from_f64(cost_usd))
+    }
+
+    pub fn cost(&self) -> Usd {
         self.cost_usd
     }
 
+    pub fn cost_usd(&self) -> f64 {
+        self.cost_usd.as_f64()
+    }
+
+    /// Keeps the logged and computed costs next to `cost_usd` for auditing.
+    pub fn with_audited_costs(mut self, logged: Option<Usd>, calculated: Option<Usd>) -> Self {
+        self.logged_cost_usd = logged;
+        self.calculated_cost_usd = calculated;
+        self
+    }
+
+    pub fn logged_cost(&self) -> Option<Usd> {
+        self.logged_cost_usd
+    }
+
+    pub fn calculated_cost(&self) -> Option<Usd> {
+        self.calculated_cost_usd
+    }
+
+    pub fn logged_cost_usd(&self) -> Option<f64> {
+        self.logged_cost_usd.map(|cost| cost.as_f64())
+    }
+
+    pub fn calculated_cost_usd(&self) -> Option<f64> {
+        self.calculated_cost_usd.map(|cost| cost.as_f64())
+    }
+
+    pub fn message_id(&self) -> Option<&str> {
+        self.message_id.as_deref()
+    }
+
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+
+    pub fn project(&self) -> Option<&str> {
+        self.project.as_deref()
+    }
+
+    pub fn cwd(&self) -> Option<&str> {
+        self.cwd.as_deref()
+    }
+
+    pub fn git_branch(&self) -> Option<&str> {
+        self.git_branch.as_deref()
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    pub fn is_sidechain(&self) -> Option<bool> {
+        self.is_sidechain
+    }
+
+    pub fn user_type(&self) -> Option<&str> {
+        self.user_type.as_deref()
+    }
+
+    pub fn source_root(&self) -> Option<&Path> {
+        self.source_root.as_deref()
+    }
+
+    pub fn source_file(&self) -> Option<&Path> {
+        self.source_file.as_deref()
+    }
+
+    pub fn pricing_profile(&self) -> Option<&str> {
+        self.pricing_profile.as_deref()
+    }
+
+    /// Key identifying the API response this entry was recorded from.
+    ///
+    /// Claude Code copies the same response into several transcripts when a
+    /// conversation is resumed or branched, so entries sharing a key are
+    /// duplicates. Entries missing either id have no key and are never merged.
+    pub fn dedup_key(&self) -> Option<String> {
+        match (&self.message_id, &self.request_id) {
+            (Some(message_id), Some(request_id)) => Some(format!("{message_id}:{request_id}")),
+            _ => None,
+        }
+    }
+
     pub fn total_tokens(&self) -> u64 {
         self.input_tokens + self.output_tokens
     }
@@ -106,11 +408,12 @@ impl UsageEntry {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct TokenCounts {
     input_tokens: u64,
     output_tokens: u64,
     cache_creation_input_tokens: u64,
+    cache_creation_1h_input_tokens: u64,
     cache_read_input_tokens: u64,
 }
 
@@ -120,6 +423,7 @@ impl TokenCounts {
             input_tokens: 0,
             output_tokens: 0,
             cache_creation_input_tokens: 0,
+            cache_creation_1h_input_tokens: 0,
             cache_read_input_tokens: 0,
         }
     }
@@ -128,6 +432,7 @@ impl TokenCounts {
         self.input_tokens += entry.input_tokens;
         self.output_tokens += entry.output_tokens;
         self.cache_creation_input_tokens += entry.cache_creation_input_tokens;
+        self.cache_creation_1h_input_tokens += entry.cache_creation_1h_input_tokens;
         self.cache_read_input_tokens += entry.cache_read_input_tokens;
     }
 
@@ -143,6 +448,14 @@ impl TokenCounts {
         self.cache_creation_input_tokens
     }
 
+    pub fn cache_creation_5m_input_tokens(&self) -> u64 {
+        self.cache_creation_input_tokens - self.cache_creation_1h_input_tokens
+    }
+
+    pub fn cache_creation_1h_input_tokens(&self) -> u64 {
+        self.cache_creation_1h_input_tokens
+    }
+
     pub fn cache_read_input_tokens(&self) -> u64 {
         self.cache_read_input_tokens
     }
@@ -165,8 +478,10 @@ pub struct SessionBlock {
     end_time: DateTime<Utc>,
     entries: Vec<UsageEntry>,
     token_counts: TokenCounts,
-    cost_usd: f64,
+    cost_usd: Usd,
     duration_minutes: f64,
+    /// The usage-limit notice logged during this block, if any.
+    limit_event: Option<LimitEvent>,
 }
 
 impl SessionBlock {
@@ -176,8 +491,9 @@ impl SessionBlock {
             end_time,
             entries: Vec::new(),
             token_counts: TokenCounts::new(),
-            cost_usd: 0.0,
+            cost_usd: Usd::ZERO,
             duration_minutes: 0.0,
+            limit_event: None,
         }
     }
 
@@ -208,6 +524,24 @@ impl SessionBlock {
         self.end_time
     }
 
+    /// Ends the block early, e.g. when a logged reset time shows the next
+    /// window started sooner than guessed.
+    pub fn set_end_time(&mut self, end_time: DateTime<Utc>) {
+        self.end_time = end_time;
+    }
+
+    pub fn set_limit_event(&mut self, event: LimitEvent) {
+        self.limit_event = Some(event);
+    }
+
+    pub fn limit_event(&self) -> Option<&LimitEvent> {
+        self.limit_event.as_ref()
+    }
+
+    pub fn hit_limit(&self) -> bool {
+        self.limit_event.is_some()
+    }
+
     pub fn entries(&self) -> &[UsageEntry] {
         &self.entries
     }
@@ -216,10 +550,14 @@ impl SessionBlock {
         &self.token_counts
     }
 
-    pub fn cost_usd(&self) -> f64 {
+    pub fn cost(&self) -> Usd {
         self.cost_usd
     }
 
+    pub fn cost_usd(&self) -> f64 {
+        self.cost_usd.as_f64()
+    }
+
     pub fn duration_minutes(&self) -> f64 {
         self.duration_minutes
     }
@@ -248,6 +586,68 @@ impl SessionBlock {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct ProjectUsage {
+    token_counts: TokenCounts,
+    weighted_tokens: f64,
+    cost_usd: Usd,
+    request_count: usize,
+    first_activity: DateTime<Utc>,
+    last_activity: DateTime<Utc>,
+}
+
+impl ProjectUsage {
+    pub fn new(first_activity: DateTime<Utc>) -> Self {
+        Self {
+            token_counts: TokenCounts::new(),
+            weighted_tokens: 0.0,
+            cost_usd: Usd::ZERO,
+            request_count: 0,
+            first_activity,
+            last_activity: first_activity,
+        }
+    }
+
+    /// Adds `entry`, which used `units` quota units under the limit model.
+    pub fn add_entry(&mut self, entry: &UsageEntry, units: f64) {
+        self.token_counts.add_entry(entry);
+        self.weighted_tokens += units;
+        self.cost_usd += entry.cost_usd;
+        self.request_count += 1;
+        self.first_activity = self.first_activity.min(entry.timestamp);
+        self.last_activity = self.last_activity.max(entry.timestamp);
+    }
+
+    pub fn token_counts(&self) -> &TokenCounts {
+        &self.token_counts
+    }
+
+    /// Quota units used under the monitor's limit model.
+    pub fn weighted_tokens(&self) -> f64 {
+        self.weighted_tokens
+    }
+
+    pub fn cost(&self) -> Usd {
+        self.cost_usd
+    }
+
+    pub fn cost_usd(&self) -> f64 {
+        self.cost_usd.as_f64()
+    }
+
+    pub fn request_count(&self) -> usize {
+        self.request_count
+    }
+
+    pub fn first_activity(&self) -> DateTime<Utc> {
+        self.first_activity
+    }
+
+    pub fn last_activity(&self) -> DateTime<Utc> {
+        self.last_activity
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BurnRate {
     tokens_per_minute: f64,
@@ -278,19 +678,19 @@ impl BurnRate {
 #[derive(Debug, Clone)]
 pub struct UsageProjection {
     current_tokens: u64,
-    current_cost: f64,
+    current_cost: Usd,
     projected_additional_tokens: u64,
-    projected_additional_cost: f64,
+    projected_additional_cost: Usd,
     projected_total_tokens: u64,
-    projected_total_cost: f64,
+    projected_total_cost: Usd,
 }
 
 impl UsageProjection {
     pub fn new(
         current_tokens: u64,
-        current_cost: f64,
+        current_cost: Usd,
         projected_additional_tokens: u64,
-        projected_additional_cost: f64,
+        projected_additional_cost: Usd,
     ) -> Self {
         Self {
             current_tokens,
@@ -307,6 +707,10 @@ impl UsageProjection {
     }
 
     pub fn current_cost(&self) -> f64 {
+        self.current_cost.as_f64()
+    }
+
+    pub fn current_usd(&self) -> Usd {
         self.current_cost
     }
 
@@ -315,6 +719,10 @@ impl UsageProjection {
     }
 
     pub fn projected_additional_cost(&self) -> f64 {
+        self.projected_additional_cost.as_f64()
+    }
+
+    pub fn projected_additional_usd(&self) -> Usd {
         self.projected_additional_cost
     }
 
@@ -323,6 +731,10 @@ impl UsageProjection {
     }
 
     pub fn projected_total_cost(&self) -> f64 {
+        self.projected_total_cost.as_f64()
+    }
+
+    pub fn projected_total_usd(&self) -> Usd {
         self.projected_total_cost
     }
 }
@@ -332,10 +744,16 @@ pub struct ModelPricing {
     input_cost_per_token: f64,
     output_cost_per_token: f64,
     cache_creation_input_token_cost: f64,
+    cache_creation_1h_input_token_cost: f64,
     cache_read_input_token_cost: f64,
+    tiers: Vec<(u64, ModelPricing)>,
+    effective_from: Option<DateTime<Utc>>,
+    effective_until: Option<DateTime<Utc>>,
 }
 
 impl ModelPricing {
+    /// `cache_creation_input_token_cost` is the 5-minute cache-write rate; the
+    /// 1-hour rate defaults to twice the input rate.
     pub fn new(
         input_cost_per_token: f64,
         output_cost_per_token: f64,
@@ -346,10 +764,71 @@ impl ModelPricing {
             input_cost_per_token,
             output_cost_per_token,
             cache_creation_input_token_cost,
+            cache_creation_1h_input_token_cost: input_cost_per_token * 2.0,
             cache_read_input_token_cost,
+            tiers: Vec::new(),
+            effective_from: None,
+            effective_until: None,
         }
     }
 
+    /// Limits these rates to `[from, until)`; `None` leaves that side open.
+    pub fn with_effective_period(
+        mut self,
+        from: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.effective_from = from;
+        self.effective_until = until;
+        self
+    }
+
+    pub fn effective_from(&self) -> Option<DateTime<Utc>> {
+        self.effective_from
+    }
+
+    pub fn effective_until(&self) -> Option<DateTime<Utc>> {
+        self.effective_until
+    }
+
+    pub fn is_effective_at(&self, timestamp: DateTime<Utc>) -> bool {
+        self.effective_from.is_none_or(|from| from <= timestamp)
+            && self.effective_until.is_none_or(|until| timestamp < until)
+    }
+
+    /// Charges `pricing` instead of these rates for requests whose prompt
+    /// (input plus cache tokens) is larger than `above_prompt_tokens`.
+    pub fn with_tier(mut self, above_prompt_tokens: u64, pricing: ModelPricing) -> Self {
+        self.tiers.push((above_prompt_tokens, pricing));
+        self.tiers.sort_by_key(|(above, _)| *above);
+        self
+    }
+
+    /// Replaces all tiers.
+    pub fn with_tiers(mut self, mut tiers: Vec<(u64, ModelPricing)>) -> Self {
+        tiers.sort_by_key(|(above, _)| *above);
+        self.tiers = tiers;
+        self
+    }
+
+    pub fn tiers(&self) -> &[(u64, ModelPricing)] {
+        &self.tiers
+    }
+
+    /// Rates that apply to a request with `prompt_tokens` prompt tokens.
+    pub fn rates_for_prompt(&self, prompt_tokens: u64) -> &ModelPricing {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|(above, _)| prompt_tokens > *above)
+            .map_or(self, |(_, pricing)| pricing)
+    }
+
+    pub fn with_cache_creation_1h_cost(mut self, cost_per_token: f64) -> Self {
+        self.cache_creation_1h_input_token_cost = cost_per_token;
+        self
+    }
+
     pub fn input_cost_per_token(&self) -> f64 {
         self.input_cost_per_token
     }
@@ -362,6 +841,10 @@ impl ModelPricing {
         self.cache_creation_input_token_cost
     }
 
+    pub fn cache_creation_1h_input_token_cost(&self) -> f64 {
+        self.cache_creation_1h_input_token_cost
+    }
+
     pub fn cache_read_input_token_cost(&self) -> f64 {
         self.cache_read_input_token_cost
     }
@@ -370,14 +853,20 @@ impl ModelPricing {
         &self,
         input_tokens: u64,
         output_tokens: u64,
-        cache_creation_tokens: u64,
+        cache_creation_5m_tokens: u64,
+        cache_creation_1h_tokens: u64,
         cache_read_tokens: u64,
-    ) -> f64 {
-        let cost = (input_tokens as f64 * self.input_cost_per_token)
-            + (output_tokens as f64 * self.output_cost_per_token)
-            + (cache_creation_tokens as f64 * self.cache_creation_input_token_cost)
-            + (cache_read_tokens as f64 * self.cache_read_input_token_cost);
-
-        (cost * 1_000_000.0).round() / 1_000_000.0
+    ) -> Usd {
+        let rates = self.rates_for_prompt(
+            input_tokens + cache_creation_5m_tokens + cache_creation_1h_tokens + cache_read_tokens,
+        );
+
+        let cost = (input_tokens as f64 * rates.input_cost_per_token)
+            + (output_tokens as f64 * rates.output_cost_per_token)
+            + (cache_creation_5m_tokens as f64 * rates.cache_creation_input_token_cost)
+            + (cache_creation_1h_tokens as f64 * rates.cache_creation_1h_input_token_cost)
+            + (cache_read_tokens as f64 * rates.cache_read_input_token_cost);
+
+        Usd::from_f64(cost)
     }
 }
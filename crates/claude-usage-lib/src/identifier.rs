@@ -1,49 +1,227 @@
 use crate::data_structures::{SessionBlock, UsageEntry};
-use chrono::{DateTime, Duration, Timelike, Utc};
+use crate::limits::LimitEvent;
+use chrono::{DateTime, Duration, NaiveTime, Timelike, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+/// Where a session window starts relative to its first message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SessionRounding {
+    /// The start of the hour of the first message.
+    #[default]
+    FloorToHour,
+    /// The first message itself.
+    Exact,
+    /// Back-to-back windows from a fixed time of day (UTC), restarting at
+    /// that time every day.
+    DailyAnchor(NaiveTime),
+}
+
+impl SessionRounding {
+    fn window_start(&self, timestamp: DateTime<Utc>, duration: Duration) -> DateTime<Utc> {
+        match self {
+            SessionRounding::FloorToHour => timestamp
+                .with_minute(0)
+                .unwrap()
+                .with_second(0)
+                .unwrap()
+                .with_nanosecond(0)
+                .unwrap(),
+            SessionRounding::Exact => timestamp,
+            SessionRounding::DailyAnchor(anchor) => {
+                let day_start = anchor_before(*anchor, timestamp);
+                let windows = (timestamp - day_start).num_seconds() / duration.num_seconds().max(1);
+                day_start + duration * windows as i32
+            }
+        }
+    }
+
+    /// End of the window opened at `start`. Daily windows are cut short at
+    /// the next anchor so each day restarts on time.
+    fn window_end(&self, start: DateTime<Utc>, duration: Duration) -> DateTime<Utc> {
+        match self {
+            SessionRounding::DailyAnchor(anchor) => {
+                let next_anchor = anchor_before(*anchor, start) + Duration::days(1);
+                (start + duration).min(next_anchor)
+            }
+            _ => start + duration,
+        }
+    }
+}
+
+/// The latest `anchor` time of day at or before `timestamp`.
+fn anchor_before(anchor: NaiveTime, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+    let day_start = timestamp.date_naive().and_time(anchor).and_utc();
+    if day_start > timestamp {
+        day_start - Duration::days(1)
+    } else {
+        day_start
+    }
+}
+
+impl fmt::Display for SessionRounding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionRounding::FloorToHour => write!(f, "hour"),
+            SessionRounding::Exact => write!(f, "exact"),
+            SessionRounding::DailyAnchor(anchor) => write!(f, "daily:{}", anchor.format("%H:%M")),
+        }
+    }
+}
+
+impl FromStr for SessionRounding {
+    type Err = anyhow::Error;
+
+    /// Parses `hour`, `exact` or `daily:HH:MM`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_ascii_lowercase();
+        if let Some(anchor) = lower.strip_prefix("daily:") {
+            return NaiveTime::parse_from_str(anchor, "%H:%M")
+                .map(SessionRounding::DailyAnchor)
+                .map_err(|_| {
+                    anyhow::anyhow!("Invalid daily anchor '{}' (expected HH:MM)", anchor)
+                });
+        }
+
+        match lower.as_str() {
+            "hour" => Ok(SessionRounding::FloorToHour),
+            "exact" => Ok(SessionRounding::Exact),
+            _ => Err(anyhow::anyhow!(
+                "Unknown session rounding '{}' (expected hour, exact or daily:HH:MM)",
+                s
+            )),
+        }
+    }
+}
+
+/// Length of a usage session and where it starts. Shared by the monitor and
+/// anything that displays session windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionConfig {
+    duration: Duration,
+    rounding: SessionRounding,
+}
+
+impl SessionConfig {
+    pub fn new(duration: Duration, rounding: SessionRounding) -> Self {
+        Self { duration, rounding }
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_rounding(mut self, rounding: SessionRounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    pub fn rounding(&self) -> SessionRounding {
+        self.rounding
+    }
+
+    /// Start and end of the window a message sent at `timestamp` would open.
+    pub fn window_for(&self, timestamp: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let start = self.rounding.window_start(timestamp, self.duration);
+        (start, self.rounding.window_end(start, self.duration))
+    }
+}
+
+impl Default for SessionConfig {
+    /// Five-hour windows starting on the hour.
+    fn default() -> Self {
+        Self::new(Duration::hours(5), SessionRounding::FloorToHour)
+    }
+}
 
 pub struct SessionIdentifier {
-    session_duration: Duration,
+    config: SessionConfig,
+    /// Logged limit notices; their reset times pin down the real windows.
+    limit_events: Vec<LimitEvent>,
 }
 
 impl SessionIdentifier {
     pub fn new() -> Self {
-        Self {
-            session_duration: Duration::hours(5),
-        }
+        Self::with_config(SessionConfig::default())
     }
 
     pub fn with_duration(session_duration: Duration) -> Self {
-        Self { session_duration }
+        Self::with_config(SessionConfig::default().with_duration(session_duration))
     }
 
-    pub fn identify_blocks(&self, entries: &[UsageEntry]) -> Vec<SessionBlock> {
-        if entries.is_empty() {
-            return Vec::new();
+    pub fn with_config(config: SessionConfig) -> Self {
+        Self {
+            config,
+            limit_events: Vec::new(),
         }
+    }
+
+    pub fn config(&self) -> &SessionConfig {
+        &self.config
+    }
+
+    /// Uses the reset times in `events` instead of the configured rounding
+    /// for the windows they fall in, and attaches each event to its block.
+    pub fn set_limit_events(&mut self, events: Vec<LimitEvent>) {
+        self.limit_events = events;
+    }
+
+    pub fn limit_events(&self) -> &[LimitEvent] {
+        &self.limit_events
+    }
 
+    pub fn identify_blocks(&self, entries: &[UsageEntry]) -> Vec<SessionBlock> {
         let mut blocks = Vec::new();
-        let mut current_block = None;
+        self.extend_blocks(&mut blocks, entries);
+        blocks
+    }
 
+    /// Appends `entries` to existing `blocks`, continuing the last block or
+    /// opening new ones. Entries must be sorted and no older than the last
+    /// entry already in `blocks`.
+    pub fn extend_blocks(&self, blocks: &mut Vec<SessionBlock>, entries: &[UsageEntry]) {
         for entry in entries {
-            if let Some(ref mut block) = current_block {
-                if self.should_create_new_block(block, entry) {
-                    blocks.push(block.clone());
-                    current_block = Some(self.create_block_for_entry(entry));
-                    current_block.as_mut().unwrap().add_entry(entry.clone());
-                } else {
+            match blocks.last_mut() {
+                Some(block) if !self.should_create_new_block(block, entry) => {
+                    block.add_entry(entry.clone());
+                }
+                _ => {
+                    let mut block = self.create_block_for_entry(entry);
+                    if let Some(previous) = blocks.last_mut() {
+                        if previous.end_time() > block.start_time() {
+                            previous.set_end_time(block.start_time());
+                        }
+                    }
                     block.add_entry(entry.clone());
+                    blocks.push(block);
                 }
-            } else {
-                current_block = Some(self.create_block_for_entry(entry));
-                current_block.as_mut().unwrap().add_entry(entry.clone());
             }
         }
 
-        if let Some(block) = current_block {
-            blocks.push(block);
+        for event in &self.limit_events {
+            if let Some(block) = blocks.iter_mut().rev().find(|block| {
+                block.start_time() <= event.timestamp() && event.timestamp() < block.end_time()
+            }) {
+                block.set_limit_event(event.clone());
+            }
         }
+    }
 
-        blocks
+    /// The window a logged reset time places `timestamp` in, if any.
+    fn calibrated_window(
+        &self,
+        timestamp: DateTime<Utc>,
+    ) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        self.limit_events
+            .iter()
+            .filter_map(|event| event.resets_at())
+            .map(|resets_at| (resets_at - self.config.duration(), resets_at))
+            .find(|(start, end)| *start <= timestamp && timestamp < *end)
     }
 
     fn should_create_new_block(&self, block: &SessionBlock, entry: &UsageEntry) -> bool {
@@ -52,7 +230,15 @@ impl SessionIdentifier {
         }
 
         if let Some(last_entry) = block.entries().last() {
-            if entry.timestamp() - last_entry.timestamp() >= self.session_duration {
+            if entry.timestamp() - last_entry.timestamp() >= self.config.duration() {
+                return true;
+            }
+        }
+
+        // The block was guessed to start before the window the logs say
+        // this entry is in
+        if let Some((start, _)) = self.calibrated_window(entry.timestamp()) {
+            if block.start_time() < start {
                 return true;
             }
         }
@@ -61,21 +247,11 @@ impl SessionIdentifier {
     }
 
     fn create_block_for_entry(&self, entry: &UsageEntry) -> SessionBlock {
-        let start_time = self.round_to_hour(entry.timestamp());
-        let end_time = start_time + self.session_duration;
-
+        let (start_time, end_time) = self
+            .calibrated_window(entry.timestamp())
+            .unwrap_or_else(|| self.config.window_for(entry.timestamp()));
         SessionBlock::new(start_time, end_time)
     }
-
-    fn round_to_hour(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
-        timestamp
-            .with_minute(0)
-            .unwrap()
-            .with_second(0)
-            .unwrap()
-            .with_nanosecond(0)
-            .unwrap()
-    }
 }
 
 impl Default for SessionIdentifier {
@@ -180,6 +356,37 @@ mod tests {
         assert_eq!(blocks[1].entries().len(), 1);
     }
 
+    #[test]
+    fn test_extend_blocks_matches_identify_blocks() {
+        let identifier = SessionIdentifier::new();
+        let base_time = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        let entries: Vec<UsageEntry> = [0, 1, 6, 7]
+            .iter()
+            .map(|hours| {
+                UsageEntry::new(
+                    base_time + Duration::hours(*hours),
+                    "claude-3-sonnet-20240229".to_string(),
+                    100,
+                    50,
+                    0,
+                    0,
+                    0.001,
+                )
+            })
+            .collect();
+
+        let mut blocks = identifier.identify_blocks(&entries[..1]);
+        identifier.extend_blocks(&mut blocks, &entries[1..]);
+
+        let expected = identifier.identify_blocks(&entries);
+        assert_eq!(blocks.len(), expected.len());
+        for (block, expected) in blocks.iter().zip(&expected) {
+            assert_eq!(block.start_time(), expected.start_time());
+            assert_eq!(block.entries().len(), expected.entries().len());
+        }
+    }
+
     #[test]
     fn test_time_boundary_creates_new_block() {
         let identifier = SessionIdentifier::new();
@@ -209,4 +416,132 @@ mod tests {
         let blocks = identifier.identify_blocks(&entries);
         assert_eq!(blocks.len(), 2);
     }
+
+    #[test]
+    fn test_session_rounding_policies() {
+        let timestamp = Utc.with_ymd_and_hms(2024, 1, 1, 12, 30, 0).unwrap();
+        let hours =
+            |hour: u32, minute: u32| Utc.with_ymd_and_hms(2024, 1, 1, hour, minute, 0).unwrap();
+
+        let config = SessionConfig::default();
+        assert_eq!(config.window_for(timestamp), (hours(12, 0), hours(17, 0)));
+
+        let config = config.with_rounding(SessionRounding::Exact);
+        assert_eq!(config.window_for(timestamp), (timestamp, hours(17, 30)));
+
+        let anchor: SessionRounding = "daily:08:15".parse().unwrap();
+        let config = config.with_rounding(anchor);
+        assert_eq!(config.window_for(timestamp), (hours(8, 15), hours(13, 15)));
+        let early = hours(3, 0);
+        assert_eq!(
+            config.window_for(early).0,
+            Utc.with_ymd_and_hms(2023, 12, 31, 23, 15, 0).unwrap()
+        );
+
+        // The last window of the day stops at the next anchor
+        assert_eq!(config.window_for(hours(5, 0)), (hours(4, 15), hours(8, 15)));
+
+        assert_eq!(anchor.to_string(), "daily:08:15");
+        assert!("daily:25:00".parse::<SessionRounding>().is_err());
+        assert!("weekly".parse::<SessionRounding>().is_err());
+    }
+
+    #[test]
+    fn test_daily_anchor_restarts_blocks() {
+        let identifier = SessionIdentifier::with_config(SessionConfig::new(
+            Duration::hours(5),
+            SessionRounding::DailyAnchor(NaiveTime::from_hms_opt(0, 0, 0).unwrap()),
+        ));
+        let entry = |timestamp| {
+            UsageEntry::new(
+                timestamp,
+                "claude-3-sonnet-20240229".to_string(),
+                100,
+                50,
+                0,
+                0,
+                0.001,
+            )
+        };
+        let midnight = Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap();
+
+        let blocks = identifier.identify_blocks(&[
+            entry(midnight - Duration::hours(2)),
+            entry(midnight + Duration::minutes(30)),
+        ]);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].start_time(), midnight - Duration::hours(4));
+        assert_eq!(blocks[0].end_time(), midnight);
+        assert_eq!(blocks[1].start_time(), midnight);
+        assert_eq!(blocks[1].end_time(), midnight + Duration::hours(5));
+    }
+
+    #[test]
+    fn test_configured_duration_splits_blocks() {
+        let identifier = SessionIdentifier::with_config(SessionConfig::new(
+            Duration::hours(2),
+            SessionRounding::Exact,
+        ));
+        let base_time = Utc.with_ymd_and_hms(2024, 1, 1, 12, 10, 0).unwrap();
+
+        let entries: Vec<UsageEntry> = [0, 1, 3]
+            .iter()
+            .map(|hours| {
+                UsageEntry::new(
+                    base_time + Duration::hours(*hours),
+                    "claude-3-sonnet-20240229".to_string(),
+                    100,
+                    50,
+                    0,
+                    0,
+                    0.001,
+                )
+            })
+            .collect();
+
+        let blocks = identifier.identify_blocks(&entries);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].start_time(), base_time);
+        assert_eq!(blocks[1].start_time(), base_time + Duration::hours(3));
+    }
+
+    #[test]
+    fn test_limit_event_calibrates_blocks() {
+        let at =
+            |hour: u32, minute: u32| Utc.with_ymd_and_hms(2024, 1, 1, hour, minute, 0).unwrap();
+        let entries: Vec<UsageEntry> = [at(10, 10), at(12, 30), at(14, 0)]
+            .into_iter()
+            .map(|timestamp| {
+                UsageEntry::new(
+                    timestamp,
+                    "claude-3-sonnet-20240229".to_string(),
+                    100,
+                    50,
+                    0,
+                    0,
+                    0.001,
+                )
+            })
+            .collect();
+
+        let mut identifier = SessionIdentifier::new();
+        assert_eq!(identifier.identify_blocks(&entries).len(), 1);
+
+        let event = LimitEvent::new(at(14, 30), Some(at(17, 0)), "usage limit reached");
+        identifier.set_limit_events(vec![event.clone()]);
+        let blocks = identifier.identify_blocks(&entries);
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(
+            (blocks[0].start_time(), blocks[0].end_time()),
+            (at(10, 0), at(12, 0))
+        );
+        assert_eq!(
+            (blocks[1].start_time(), blocks[1].end_time()),
+            (at(12, 0), at(17, 0))
+        );
+        assert_eq!(blocks[1].entries().len(), 2);
+        assert!(!blocks[0].hit_limit());
+        assert_eq!(blocks[1].limit_event(), Some(&event));
+    }
 }
@@ -1,18 +1,38 @@
+pub mod archive;
 pub mod calculator;
+pub mod config;
+pub mod cost;
+pub mod currency;
 pub mod data_structures;
 pub mod identifier;
+pub mod limits;
 pub mod loader;
 pub mod monitor;
+pub mod plans;
 pub mod pricing;
+pub mod report;
+pub mod weekly;
 
+pub use archive::UsageArchive;
 pub use calculator::Calculator;
+pub use config::config_dir;
+pub use cost::{CostMode, Usd};
+pub use currency::{Currency, ExchangeRates};
 pub use data_structures::{
-    BurnRate, ClaudePlan, SessionBlock, TokenCounts, UsageEntry, UsageProjection,
+    BurnRate, ClaudePlan, LimitEstimate, LimitSource, ProjectUsage, SessionBlock, TokenCounts,
+    UsageEntry, UsageProjection,
 };
-pub use identifier::SessionIdentifier;
-pub use loader::DataLoader;
+pub use identifier::{SessionConfig, SessionIdentifier, SessionRounding};
+pub use limits::{
+    builtin_limit_model, ConfiguredLimitModel, CostBased, LimitEvent, LimitModel, RawTokens,
+    WeightedTokens, WeightedTokensWithCache,
+};
+pub use loader::{discover_data_roots, DataLoader};
 pub use monitor::UsageMonitor;
-pub use pricing::PricingProvider;
+pub use plans::{CustomPlan, PlanRegistry};
+pub use pricing::{MatchRule, PricingProvider, ResolvedModel, DEFAULT_PROFILE};
+pub use report::{CostDiscrepancy, LoadReport, SkipReason};
+pub use weekly::{WeeklyBudget, WeeklyConfig, WeeklyReset, WeeklyUsage};
 
 pub use anyhow::Result;
 pub use chrono::{DateTime, Duration, Utc};
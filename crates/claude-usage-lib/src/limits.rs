@@ -0,0 +1,388 @@
+use crate::config::find_config_file;
+use crate::data_structures::{SessionBlock, UsageEntry};
+use crate::pricing::PricingProvider;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Turns usage into the "quota units" that plan limits are measured in.
+///
+/// The real quota formula is not public, so the monitor lets callers pick
+/// one; [`WeightedTokens`] is the default.
+pub trait LimitModel: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Quota units consumed by `entry`.
+    fn units(&self, entry: &UsageEntry, pricing: &PricingProvider) -> f64;
+
+    fn block_units(&self, block: &SessionBlock, pricing: &PricingProvider) -> f64 {
+        block
+            .entries()
+            .iter()
+            .map(|entry| self.units(entry, pricing))
+            .sum()
+    }
+}
+
+/// Input plus output tokens, ignoring the model.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RawTokens;
+
+impl LimitModel for RawTokens {
+    fn name(&self) -> &str {
+        "raw"
+    }
+
+    fn units(&self, entry: &UsageEntry, _pricing: &PricingProvider) -> f64 {
+        entry.total_tokens() as f64
+    }
+}
+
+/// Input plus output tokens times the model weight.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WeightedTokens;
+
+impl LimitModel for WeightedTokens {
+    fn name(&self) -> &str {
+        "weighted"
+    }
+
+    fn units(&self, entry: &UsageEntry, pricing: &PricingProvider) -> f64 {
+        entry.total_tokens() as f64 * pricing.get_model_weight(entry.model())
+    }
+}
+
+/// All tokens, including cache writes and reads, times the model weight.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WeightedTokensWithCache;
+
+impl LimitModel for WeightedTokensWithCache {
+    fn name(&self) -> &str {
+        "with-cache"
+    }
+
+    fn units(&self, entry: &UsageEntry, pricing: &PricingProvider) -> f64 {
+        entry.all_tokens() as f64 * pricing.get_model_weight(entry.model())
+    }
+}
+
+/// The entry's cost scaled to token-like units.
+#[derive(Debug, Clone, Copy)]
+pub struct CostBased {
+    units_per_dollar: f64,
+}
+
+impl CostBased {
+    pub fn new(units_per_dollar: f64) -> Self {
+        Self { units_per_dollar }
+    }
+
+    pub fn units_per_dollar(&self) -> f64 {
+        self.units_per_dollar
+    }
+}
+
+impl Default for CostBased {
+    /// 100,000 units per dollar, roughly one unit per Sonnet token at a
+    /// typical input/output mix.
+    fn default() -> Self {
+        Self::new(100_000.0)
+    }
+}
+
+impl LimitModel for CostBased {
+    fn name(&self) -> &str {
+        "cost"
+    }
+
+    fn units(&self, entry: &UsageEntry, _pricing: &PricingProvider) -> f64 {
+        entry.cost_usd() * self.units_per_dollar
+    }
+}
+
+/// Per-token-kind factors and model weights read from a config file, e.g.
+///
+/// ```toml
+/// input = 1.0
+/// output = 1.0
+/// cache_write = 1.0
+/// cache_read = 0.1
+///
+/// [weights]
+/// "claude-opus-4-1-20250805" = 5.0
+/// ```
+///
+/// Models without a weight here use the pricing table's weight, unless
+/// `use_model_weights = false`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ConfiguredLimitModel {
+    input: f64,
+    output: f64,
+    cache_write: f64,
+    cache_read: f64,
+    use_model_weights: bool,
+    weights: HashMap<String, f64>,
+}
+
+impl Default for ConfiguredLimitModel {
+    /// Same units as [`WeightedTokens`].
+    fn default() -> Self {
+        Self {
+            input: 1.0,
+            output: 1.0,
+            cache_write: 0.0,
+            cache_read: 0.0,
+            use_model_weights: true,
+            weights: HashMap::new(),
+        }
+    }
+}
+
+impl ConfiguredLimitModel {
+    /// Reads a TOML or JSON file, chosen by extension.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read limit model: {}", path.display()))?;
+
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str(&content)
+                .with_context(|| format!("Invalid limit model: {}", path.display()))
+        } else {
+            serde_json::from_str(&content)
+                .with_context(|| format!("Invalid limit model: {}", path.display()))
+        }
+    }
+
+    /// `limits.toml` or `limits.json` in [`crate::config_dir`], whichever
+    /// exists.
+    pub fn default_config_path() -> Option<PathBuf> {
+        find_config_file(&["limits.toml", "limits.json"])
+    }
+
+    fn weight(&self, model: &str, pricing: &PricingProvider) -> f64 {
+        match self.weights.get(model) {
+            Some(weight) => *weight,
+            None if self.use_model_weights => pricing.get_model_weight(model),
+            None => 1.0,
+        }
+    }
+}
+
+impl LimitModel for ConfiguredLimitModel {
+    fn name(&self) -> &str {
+        "configured"
+    }
+
+    fn units(&self, entry: &UsageEntry, pricing: &PricingProvider) -> f64 {
+        let tokens = entry.input_tokens() as f64 * self.input
+            + entry.output_tokens() as f64 * self.output
+            + entry.cache_creation_input_tokens() as f64 * self.cache_write
+            + entry.cache_read_input_tokens() as f64 * self.cache_read;
+
+        tokens * self.weight(entry.model(), pricing)
+    }
+}
+
+/// Built-in limit model by name: `raw`, `weighted`, `with-cache` or `cost`.
+pub fn builtin_limit_model(name: &str) -> Option<Box<dyn LimitModel>> {
+    match name {
+        "raw" => Some(Box::new(RawTokens)),
+        "weighted" => Some(Box::new(WeightedTokens)),
+        "with-cache" => Some(Box::new(WeightedTokensWithCache)),
+        "cost" => Some(Box::new(CostBased::default())),
+        _ => None,
+    }
+}
+
+/// Openings of the limit notices Claude Code writes, lowercased.
+const LIMIT_NOTICES: &[&str] = &[
+    "claude ai usage limit reached",
+    "claude usage limit reached",
+    "usage limit reached",
+    "5-hour limit reached",
+    "weekly limit reached",
+    "opus weekly limit reached",
+];
+
+/// A "usage limit reached" notice found in the transcripts, with the reset
+/// time it advertises when one could be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitEvent {
+    timestamp: DateTime<Utc>,
+    resets_at: Option<DateTime<Utc>>,
+    message: String,
+}
+
+impl LimitEvent {
+    pub fn new(timestamp: DateTime<Utc>, resets_at: Option<DateTime<Utc>>, message: &str) -> Self {
+        Self {
+            timestamp,
+            resets_at,
+            message: message.to_string(),
+        }
+    }
+
+    /// Recognizes limit notices such as `Claude AI usage limit
+    /// reached|1736950800` (reset as a Unix timestamp) and `5-hour limit
+    /// reached ∙ resets 3pm` (reset as a local clock time after `timestamp`).
+    /// The text must open with one of the notices; mentions of limits
+    /// elsewhere in a message are not events.
+    pub fn parse(text: &str, timestamp: DateTime<Utc>) -> Option<Self> {
+        let lower = text.trim().to_ascii_lowercase();
+        if !LIMIT_NOTICES.iter().any(|notice| lower.starts_with(notice)) {
+            return None;
+        }
+
+        let resets_at = match lower.split_once('|') {
+            Some((_, unix)) => unix
+                .trim()
+                .parse::<i64>()
+                .ok()
+                .and_then(|seconds| Utc.timestamp_opt(seconds, 0).single()),
+            None => {
+                parse_reset_clock(&lower).and_then(|time| next_occurrence(&Local, time, timestamp))
+            }
+        };
+
+        Some(Self::new(timestamp, resets_at, text.trim()))
+    }
+
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    pub fn resets_at(&self) -> Option<DateTime<Utc>> {
+        self.resets_at
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// Reads the clock time after "reset"/"resets" (optionally "at"), e.g.
+/// `3pm`, `3:30 pm` or `15:00`.
+fn parse_reset_clock(text: &str) -> Option<NaiveTime> {
+    let (_, rest) = text.split_once("reset")?;
+    let rest = rest.trim_start_matches('s').trim_start();
+    let rest = rest.strip_prefix("at ").unwrap_or(rest).trim_start();
+
+    let clock_len = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == ':'))
+        .unwrap_or(rest.len());
+    let (clock, suffix) = rest.split_at(clock_len);
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        None => (clock.parse::<u32>().ok()?, 0),
+    };
+
+    let suffix = suffix.trim_start();
+    let hour = if suffix.starts_with("am") {
+        hour % 12
+    } else if suffix.starts_with("pm") {
+        hour % 12 + 12
+    } else {
+        hour
+    };
+
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// The first time after `after` that the clock in `tz` reads `time`.
+fn next_occurrence<Tz: TimeZone>(
+    tz: &Tz,
+    time: NaiveTime,
+    after: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let local_date = after.with_timezone(tz).date_naive();
+    (0..=1)
+        .filter_map(|days| {
+            tz.from_local_datetime(&(local_date + Duration::days(days)).and_time(time))
+                .earliest()
+        })
+        .map(|reset| reset.with_timezone(&Utc))
+        .find(|reset| *reset > after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn opus_entry() -> UsageEntry {
+        UsageEntry::new(
+            Utc::now(),
+            "claude-opus-4-20250514".to_string(),
+            100,
+            50,
+            1000,
+            2000,
+            0.5,
+        )
+    }
+
+    #[test]
+    fn test_builtin_models() {
+        let pricing = PricingProvider::new();
+        let entry = opus_entry();
+
+        let units = |name| builtin_limit_model(name).unwrap().units(&entry, &pricing);
+        assert_eq!(units("raw"), 150.0);
+        assert_eq!(units("weighted"), 750.0);
+        assert_eq!(units("with-cache"), 15_750.0);
+        assert_eq!(units("cost"), 50_000.0);
+        assert!(builtin_limit_model("unknown").is_none());
+    }
+
+    #[test]
+    fn test_configured_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("limits.toml");
+        std::fs::write(
+            &path,
+            r#"
+cache_read = 0.1
+
+[weights]
+"claude-opus-4-20250514" = 2.0
+"#,
+        )
+        .unwrap();
+
+        let model = ConfiguredLimitModel::from_file(&path).unwrap();
+        let pricing = PricingProvider::new();
+        assert_eq!(model.units(&opus_entry(), &pricing), 700.0);
+    }
+
+    #[test]
+    fn test_parse_limit_events() {
+        let timestamp = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap();
+
+        let event =
+            LimitEvent::parse("Claude AI usage limit reached|1736960400", timestamp).unwrap();
+        assert_eq!(
+            event.resets_at(),
+            Some(Utc.with_ymd_and_hms(2025, 1, 15, 17, 0, 0).unwrap())
+        );
+
+        let clock = parse_reset_clock("5-hour limit reached ∙ resets 3pm").unwrap();
+        assert_eq!(clock, NaiveTime::from_hms_opt(15, 0, 0).unwrap());
+        let clock = parse_reset_clock("your limit will reset at 9:30 am (utc).").unwrap();
+        assert_eq!(clock, NaiveTime::from_hms_opt(9, 30, 0).unwrap());
+        assert_eq!(
+            next_occurrence(&Utc, clock, timestamp),
+            Some(Utc.with_ymd_and_hms(2025, 1, 16, 9, 30, 0).unwrap())
+        );
+
+        assert!(LimitEvent::parse("Claude usage limit reached", timestamp)
+            .unwrap()
+            .resets_at()
+            .is_none());
+        assert!(LimitEvent::parse("Rate limits are discussed here", timestamp).is_none());
+        assert!(LimitEvent::parse("What if my usage limit reached zero?", timestamp).is_none());
+    }
+}
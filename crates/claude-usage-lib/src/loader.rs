@@ -1,171 +1,691 @@
+use crate::cost::{CostMode, Usd};
 use crate::data_structures::UsageEntry;
-use crate::pricing::PricingProvider;
+use crate::limits::LimitEvent;
+use crate::pricing::{MatchRule, PricingProvider};
+use crate::report::{CostDiscrepancy, LoadReport, SkipReason};
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde_json::Value;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, Metadata};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 pub struct DataLoader {
     pricing_provider: PricingProvider,
+    cursors: HashMap<PathBuf, FileCursor>,
+    seen_keys: HashSet<String>,
+    workers: usize,
+    cost_mode: CostMode,
+    audit_tolerance: f64,
+    /// Pricing profile for the entries under each data root.
+    root_profiles: HashMap<PathBuf, String>,
+}
+
+/// Default largest difference, in dollars, between logged and computed costs
+/// that audit mode accepts.
+pub const DEFAULT_AUDIT_TOLERANCE: f64 = 0.0001;
+
+/// Read position within a file that has already been loaded incrementally.
+#[derive(Debug, Clone, Copy)]
+struct FileCursor {
+    offset: u64,
+    identity: u64,
+}
+
+/// Result of reading one file from its cursor, before it is merged into an
+/// [`IncrementalLoad`].
+struct AppendedLines {
+    entries: Vec<UsageEntry>,
+    report: LoadReport,
+    cursor: FileCursor,
+    rewritten: bool,
+}
+
+/// Entries appended since the previous incremental load.
+#[derive(Debug, Default)]
+pub struct IncrementalLoad {
+    entries: Vec<UsageEntry>,
+    report: LoadReport,
+    rewritten: bool,
+}
+
+impl IncrementalLoad {
+    pub fn entries(&self) -> &[UsageEntry] {
+        &self.entries
+    }
+
+    pub fn into_entries(self) -> Vec<UsageEntry> {
+        self.entries
+    }
+
+    /// Diagnostics for the lines read by this load. Files are counted the
+    /// first time they are seen, so reports from successive loads can be
+    /// merged.
+    pub fn report(&self) -> &LoadReport {
+        &self.report
+    }
+
+    pub fn into_parts(self) -> (Vec<UsageEntry>, LoadReport) {
+        (self.entries, self.report)
+    }
+
+    /// True when a previously read file was truncated or replaced. The new
+    /// entries are then incomplete and callers should reload from scratch.
+    pub fn rewritten(&self) -> bool {
+        self.rewritten
+    }
 }
 
 impl DataLoader {
     pub fn new() -> Self {
         Self {
             pricing_provider: PricingProvider::new(),
+            cursors: HashMap::new(),
+            seen_keys: HashSet::new(),
+            workers: 1,
+            cost_mode: CostMode::Auto,
+            audit_tolerance: DEFAULT_AUDIT_TOLERANCE,
+            root_profiles: HashMap::new(),
+        }
+    }
+
+    /// Prices entries without a logged cost using `pricing_provider`.
+    pub fn with_pricing(mut self, pricing_provider: PricingProvider) -> Self {
+        self.set_pricing(pricing_provider);
+        self
+    }
+
+    pub fn set_pricing(&mut self, pricing_provider: PricingProvider) {
+        self.pricing_provider = pricing_provider;
+    }
+
+    /// Prices the entries loaded from `root` with the pricing profile
+    /// `profile` instead of the default one.
+    pub fn with_root_profile<P: AsRef<Path>>(mut self, root: P, profile: &str) -> Self {
+        self.set_root_profile(root, profile);
+        self
+    }
+
+    pub fn set_root_profile<P: AsRef<Path>>(&mut self, root: P, profile: &str) {
+        let root = root.as_ref();
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        self.root_profiles.insert(root, profile.to_string());
+    }
+
+    /// Profile of the deepest configured root containing `path`.
+    fn root_profile(&self, path: &Path) -> Option<&str> {
+        if self.root_profiles.is_empty() {
+            return None;
         }
+
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.root_profiles
+            .iter()
+            .filter(|(root, _)| path.starts_with(root))
+            .max_by_key(|(root, _)| root.components().count())
+            .map(|(_, profile)| profile.as_str())
+    }
+
+    pub fn with_cost_mode(mut self, cost_mode: CostMode) -> Self {
+        self.set_cost_mode(cost_mode);
+        self
+    }
+
+    pub fn set_cost_mode(&mut self, cost_mode: CostMode) {
+        self.cost_mode = cost_mode;
+    }
+
+    pub fn cost_mode(&self) -> CostMode {
+        self.cost_mode
+    }
+
+    /// Reports audited entries whose costs differ by more than `tolerance`
+    /// dollars.
+    pub fn with_audit_tolerance(mut self, tolerance: f64) -> Self {
+        self.set_audit_tolerance(tolerance);
+        self
+    }
+
+    pub fn set_audit_tolerance(&mut self, tolerance: f64) {
+        self.audit_tolerance = tolerance.abs();
+    }
+
+    /// Parses files on `workers` threads. One worker (the default) parses
+    /// sequentially; the result is the same either way.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.set_workers(workers);
+        self
+    }
+
+    pub fn set_workers(&mut self, workers: usize) {
+        self.workers = workers.max(1);
+    }
+
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Number of threads the machine can run in parallel, for use with
+    /// [`DataLoader::with_workers`].
+    pub fn available_workers() -> usize {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
     }
 
     pub fn load_from_file<P: AsRef<Path>>(&self, path: P) -> Result<Vec<UsageEntry>> {
-        let file = File::open(&path)
-            .with_context(|| format!("Failed to open file: {}", path.as_ref().display()))?;
+        self.load_from_file_with_report(path)
+            .map(|(entries, _)| entries)
+    }
+
+    pub fn load_from_file_with_report<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<(Vec<UsageEntry>, LoadReport)> {
+        let mut report = LoadReport::new();
+        let mut entries = self.load_file(path.as_ref(), &mut report)?;
+        let duplicates = deduplicate_entries(&mut entries);
+        report.record_duplicates_removed(duplicates);
+        Ok((entries, report))
+    }
+
+    fn load_file(&self, path: &Path, report: &mut LoadReport) -> Result<Vec<UsageEntry>> {
+        let file =
+            File::open(path).with_context(|| format!("Failed to open file: {}", path.display()))?;
+
+        report.record_file_scanned();
+        self.parse_reader(BufReader::new(file), path, report)
+    }
+
+    pub fn load_from_directory<P: AsRef<Path>>(&self, dir_path: P) -> Result<Vec<UsageEntry>> {
+        self.load_from_directory_with_report(dir_path)
+            .map(|(entries, _)| entries)
+    }
+
+    /// Loads every `.jsonl` file under `dir_path` and drops entries that were
+    /// copied into more than one transcript. Returns the remaining entries,
+    /// sorted by timestamp, along with a report of what was read and skipped.
+    pub fn load_from_directory_with_report<P: AsRef<Path>>(
+        &self,
+        dir_path: P,
+    ) -> Result<(Vec<UsageEntry>, LoadReport)> {
+        self.load_from_roots(&[dir_path])
+    }
+
+    /// Loads and merges several data roots, tagging each entry with the root
+    /// it came from. Duplicates are removed across all roots. Roots that
+    /// cannot be read are reported and skipped unless none of them can.
+    pub fn load_from_roots<P: AsRef<Path>>(
+        &self,
+        roots: &[P],
+    ) -> Result<(Vec<UsageEntry>, LoadReport)> {
+        let mut all_entries = Vec::new();
+        let mut report = LoadReport::new();
+        let mut last_error = None;
+        let mut loaded_any = false;
+
+        for root in roots {
+            let root = root.as_ref();
+            match self.load_root(root, &mut report) {
+                Ok(mut root_entries) => {
+                    all_entries.append(&mut root_entries);
+                    loaded_any = true;
+                }
+                Err(e) => {
+                    report.record_unreadable_file(root, &e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        if let (false, Some(e)) = (loaded_any, last_error) {
+            return Err(e);
+        }
+
+        all_entries.sort_by_key(|entry| entry.timestamp());
+        let duplicates = deduplicate_entries(&mut all_entries);
+        report.record_duplicates_removed(duplicates);
+        Ok((all_entries, report))
+    }
+
+    fn load_root(&self, root: &Path, report: &mut LoadReport) -> Result<Vec<UsageEntry>> {
+        let mut files = Vec::new();
+        collect_jsonl_files(root, &mut files, report)?;
+        files.sort();
+
+        let results = self.map_files(&files, |path| {
+            let mut file_report = LoadReport::new();
+            let result = self.load_file(path, &mut file_report);
+            (result, file_report)
+        });
 
-        let reader = BufReader::new(file);
         let mut entries = Vec::new();
+        for (path, (result, file_report)) in files.iter().zip(results) {
+            report.merge(file_report);
+            match result {
+                Ok(file_entries) => entries.extend(
+                    file_entries
+                        .into_iter()
+                        .map(|entry| entry.with_source_root(Some(root.to_path_buf()))),
+                ),
+                Err(e) => report.record_unreadable_file(path, &e),
+            }
+        }
 
-        for (line_num, line) in reader.lines().enumerate() {
-            let line = line.with_context(|| format!("Failed to read line {}", line_num + 1))?;
+        Ok(entries)
+    }
 
-            if line.trim().is_empty() {
+    /// Applies `load` to every file, spreading the work over the configured
+    /// number of workers. Results are returned in the order of `files`.
+    fn map_files<T, F>(&self, files: &[PathBuf], load: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(&Path) -> T + Sync,
+    {
+        let workers = self.workers.min(files.len());
+        if workers <= 1 {
+            return files.iter().map(|path| load(path)).collect();
+        }
+
+        let next_file = AtomicUsize::new(0);
+        let mut results: Vec<(usize, T)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut loaded = Vec::new();
+                        loop {
+                            let index = next_file.fetch_add(1, Ordering::Relaxed);
+                            let Some(path) = files.get(index) else {
+                                break;
+                            };
+                            loaded.push((index, load(path)));
+                        }
+                        loaded
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("loader worker panicked"))
+                .collect()
+        });
+
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Loads only the lines appended to `path` (a file or a directory of
+    /// `.jsonl` files) since the previous call. The first call reads
+    /// everything. A trailing line without a newline is left for the next
+    /// call, since Claude Code may still be writing it.
+    pub fn load_incremental<P: AsRef<Path>>(&mut self, path: P) -> Result<IncrementalLoad> {
+        self.load_incremental_roots(&[path])
+    }
+
+    /// Incremental counterpart of [`DataLoader::load_from_roots`].
+    pub fn load_incremental_roots<P: AsRef<Path>>(
+        &mut self,
+        roots: &[P],
+    ) -> Result<IncrementalLoad> {
+        let mut update = IncrementalLoad::default();
+        let mut last_error = None;
+        let mut loaded_any = false;
+
+        for root in roots {
+            let root = root.as_ref();
+            let mut files = Vec::new();
+            if root.is_file() {
+                files.push(root.to_path_buf());
+            } else if let Err(e) = collect_jsonl_files(root, &mut files, &mut update.report) {
+                update.report.record_unreadable_file(root, &e);
+                last_error = Some(e);
                 continue;
             }
+            files.sort();
+            loaded_any = true;
 
-            match self.parse_line(&line) {
-                Ok(entry) => entries.push(entry),
-                Err(_) => {
-                    // Silently skip lines that don't contain usage data
-                    continue;
+            let results = self.map_files(&files, |path| self.read_appended_lines(path));
+            for (file_path, result) in files.iter().zip(results) {
+                match result {
+                    Ok(appended) => {
+                        self.cursors.insert(file_path.clone(), appended.cursor);
+                        update.rewritten |= appended.rewritten;
+                        update.report.merge(appended.report);
+                        update.entries.extend(
+                            appended
+                                .entries
+                                .into_iter()
+                                .map(|entry| entry.with_source_root(Some(root.to_path_buf()))),
+                        );
+                    }
+                    Err(e) => update.report.record_unreadable_file(file_path, &e),
                 }
             }
         }
 
-        Ok(entries)
+        if let (false, Some(e)) = (loaded_any, last_error) {
+            return Err(e);
+        }
+
+        update.entries.sort_by_key(|entry| entry.timestamp());
+        let before = update.entries.len();
+        let seen_keys = &mut self.seen_keys;
+        update.entries.retain(|entry| match entry.dedup_key() {
+            Some(key) => seen_keys.insert(key),
+            None => true,
+        });
+        update
+            .report
+            .record_duplicates_removed(before - update.entries.len());
+
+        Ok(update)
     }
 
-    pub fn load_from_directory<P: AsRef<Path>>(&self, dir_path: P) -> Result<Vec<UsageEntry>> {
-        let mut all_entries = Vec::new();
-        self.load_from_directory_recursive(dir_path.as_ref(), &mut all_entries)?;
-        all_entries.sort_by(|a, b| a.timestamp().cmp(&b.timestamp()));
-        Ok(all_entries)
+    /// Forgets all read positions so the next incremental load starts over.
+    pub fn reset_incremental_state(&mut self) {
+        self.cursors.clear();
+        self.seen_keys.clear();
     }
 
-    fn load_from_directory_recursive(
+    fn read_appended_lines(&self, path: &Path) -> Result<AppendedLines> {
+        let mut file =
+            File::open(path).with_context(|| format!("Failed to open file: {}", path.display()))?;
+        let metadata = file
+            .metadata()
+            .with_context(|| format!("Failed to stat file: {}", path.display()))?;
+        let identity = file_identity(&metadata);
+
+        let mut report = LoadReport::new();
+        let mut rewritten = false;
+        let mut offset = 0;
+        match self.cursors.get(path) {
+            Some(cursor) if cursor.identity != identity || metadata.len() < cursor.offset => {
+                rewritten = true;
+            }
+            Some(cursor) => offset = cursor.offset,
+            None => report.record_file_scanned(),
+        }
+
+        let mut buffer = Vec::new();
+        if metadata.len() > offset {
+            file.seek(SeekFrom::Start(offset))?;
+            file.read_to_end(&mut buffer)
+                .with_context(|| format!("Failed to read file: {}", path.display()))?;
+        }
+
+        let complete = match buffer.iter().rposition(|&b| b == b'\n') {
+            Some(last_newline) => last_newline + 1,
+            None => 0,
+        };
+
+        let entries = self.parse_reader(&buffer[..complete], path, &mut report)?;
+
+        Ok(AppendedLines {
+            entries,
+            report,
+            cursor: FileCursor {
+                offset: offset + complete as u64,
+                identity,
+            },
+            rewritten,
+        })
+    }
+
+    fn parse_reader<R: BufRead>(
         &self,
-        dir_path: &Path,
-        entries: &mut Vec<UsageEntry>,
-    ) -> Result<()> {
-        let dir = std::fs::read_dir(dir_path)
-            .with_context(|| format!("Failed to read directory: {}", dir_path.display()))?;
-
-        for entry in dir {
-            let entry = entry.context("Failed to read directory entry")?;
-            let path = entry.path();
-
-            if path.is_file() {
-                if let Some(extension) = path.extension() {
-                    if extension == "jsonl" {
-                        match self.load_from_file(&path) {
-                            Ok(mut file_entries) => entries.append(&mut file_entries),
-                            Err(e) => {
-                                eprintln!("Warning: Failed to load file {}: {}", path.display(), e);
+        reader: R,
+        path: &Path,
+        report: &mut LoadReport,
+    ) -> Result<Vec<UsageEntry>> {
+        let mut entries = Vec::new();
+        let project = project_name(path);
+        let root_profile = self.root_profile(path);
+        let source_file = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+        for (line_num, line) in reader.lines().enumerate() {
+            let line = line.with_context(|| format!("Failed to read line {}", line_num + 1))?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            report.record_line_read();
+            match self.parse_line(&line, root_profile) {
+                Ok(entry) => {
+                    if self.cost_mode == CostMode::Audit {
+                        report.record_cost_audit(CostDiscrepancy::from_entry(
+                            &entry,
+                            self.audit_tolerance,
+                        ));
+                    }
+                    let profile = entry.pricing_profile().unwrap_or_default();
+                    match self.pricing_provider.resolve_in(
+                        profile,
+                        entry.model(),
+                        entry.timestamp(),
+                    ) {
+                        None => {
+                            report.record_unknown_model(entry.model());
+                            if entry.cost().micros() == 0 {
+                                report.record_unpriced_entry();
                             }
                         }
+                        Some(resolved) if resolved.rule() != MatchRule::Exact => {
+                            report.record_resolved_model(entry.model(), &resolved)
+                        }
+                        Some(_) => {}
                     }
-                }
-            } else if path.is_dir() {
-                // Recursively search subdirectories (for project directories)
-                if let Err(e) = self.load_from_directory_recursive(&path, entries) {
-                    eprintln!(
-                        "Warning: Failed to load from directory {}: {}",
-                        path.display(),
-                        e
+                    entries.push(
+                        entry
+                            .with_project(project.clone())
+                            .with_source_file(Some(source_file.clone())),
                     );
                 }
+                Err(reason) => match self.parse_limit_event(&line) {
+                    Some(event) => report.record_limit_event(event),
+                    None => report.record_skipped_line(reason),
+                },
             }
         }
 
-        Ok(())
+        Ok(entries)
     }
 
-    fn parse_line(&self, line: &str) -> Result<UsageEntry> {
-        let json: Value = serde_json::from_str(line).context("Failed to parse JSON")?;
+    fn parse_line(&self, line: &str, root_profile: Option<&str>) -> Result<UsageEntry, SkipReason> {
+        let json: Value = serde_json::from_str(line).map_err(|_| SkipReason::InvalidJson)?;
 
         // Check if this is an assistant message with usage data
         if let Some(message) = json.get("message") {
             if let Some(usage) = message.get("usage") {
-                let timestamp = self.parse_timestamp(&json)?;
+                let timestamp = self
+                    .parse_timestamp(&json)
+                    .map_err(|_| SkipReason::InvalidTimestamp)?;
                 let model = message
                     .get("model")
                     .and_then(|v| v.as_str())
                     .unwrap_or("unknown")
                     .to_string();
+                let message_id = self.extract_string(message, "id").ok();
+                let request_id = self.extract_string(&json, "requestId").ok();
 
-                let input_tokens = self.extract_u64(usage, "input_tokens")?;
-                let output_tokens = self.extract_u64(usage, "output_tokens")?;
-                let cache_creation_input_tokens = self
-                    .extract_u64(usage, "cache_creation_input_tokens")
-                    .unwrap_or(0);
+                let input_tokens = self
+                    .extract_u64(usage, "input_tokens")
+                    .map_err(|_| SkipReason::MissingUsage)?;
+                let output_tokens = self
+                    .extract_u64(usage, "output_tokens")
+                    .map_err(|_| SkipReason::MissingUsage)?;
+                let (cache_creation_input_tokens, cache_creation_1h_tokens) =
+                    self.extract_cache_creation(usage);
                 let cache_read_input_tokens = self
                     .extract_u64(usage, "cache_read_input_tokens")
                     .unwrap_or(0);
 
-                // Calculate cost using our pricing (since cost_usd might not be present)
-                let cost_usd = self.extract_f64(&json, "cost_usd").unwrap_or_else(|_| {
-                    self.pricing_provider
-                        .calculate_cost(
-                            &model,
-                            input_tokens,
-                            output_tokens,
-                            cache_creation_input_tokens,
-                            cache_read_input_tokens,
-                        )
-                        .unwrap_or(0.0)
-                });
-
-                return Ok(UsageEntry::new(
+                let logged_cost = self.extract_f64(&json, "cost_usd").ok().map(Usd::from_f64);
+
+                let entry = UsageEntry::new(
                     timestamp,
                     model,
                     input_tokens,
                     output_tokens,
                     cache_creation_input_tokens,
                     cache_read_input_tokens,
-                    cost_usd,
-                ));
+                    0.0,
+                )
+                .with_cache_creation_1h_tokens(cache_creation_1h_tokens)
+                .with_ids(message_id, request_id);
+                let entry = self.apply_cost_mode(entry, logged_cost, root_profile);
+
+                return Ok(self.with_line_metadata(entry, &json));
             }
         }
 
         // Fallback: try to parse as the simple format used in tests
         if let Some(usage) = json.get("usage") {
-            let timestamp = self.parse_timestamp(&json)?;
-            let model = self.extract_string(&json, "model")?;
+            let timestamp = self
+                .parse_timestamp(&json)
+                .map_err(|_| SkipReason::InvalidTimestamp)?;
+            let model = self
+                .extract_string(&json, "model")
+                .map_err(|_| SkipReason::MissingUsage)?;
 
-            let input_tokens = self.extract_u64(usage, "input_tokens")?;
-            let output_tokens = self.extract_u64(usage, "output_tokens")?;
-            let cache_creation_input_tokens = self
-                .extract_u64(usage, "cache_creation_input_tokens")
-                .unwrap_or(0);
+            let input_tokens = self
+                .extract_u64(usage, "input_tokens")
+                .map_err(|_| SkipReason::MissingUsage)?;
+            let output_tokens = self
+                .extract_u64(usage, "output_tokens")
+                .map_err(|_| SkipReason::MissingUsage)?;
+            let (cache_creation_input_tokens, cache_creation_1h_tokens) =
+                self.extract_cache_creation(usage);
             let cache_read_input_tokens = self
                 .extract_u64(usage, "cache_read_input_tokens")
                 .unwrap_or(0);
 
-            let cost_usd = self.extract_f64(&json, "cost_usd").unwrap_or(0.0);
+            let logged_cost = self.extract_f64(&json, "cost_usd").ok().map(Usd::from_f64);
+            let message_id = self.extract_string(&json, "message_id").ok();
+            let request_id = self.extract_string(&json, "request_id").ok();
 
-            Ok(UsageEntry::new(
+            let entry = UsageEntry::new(
                 timestamp,
                 model,
                 input_tokens,
                 output_tokens,
                 cache_creation_input_tokens,
                 cache_read_input_tokens,
-                cost_usd,
-            ))
+                0.0,
+            )
+            .with_cache_creation_1h_tokens(cache_creation_1h_tokens)
+            .with_ids(message_id, request_id);
+            let entry = self.apply_cost_mode(entry, logged_cost, root_profile);
+
+            Ok(self.with_line_metadata(entry, &json))
         } else {
-            Err(anyhow::anyhow!("No usage data found in this entry"))
+            Err(SkipReason::MissingUsage)
         }
     }
 
+    /// Sets the entry's pricing profile, and its cost from the logged value
+    /// and/or the pricing table, depending on the cost mode.
+    fn apply_cost_mode(
+        &self,
+        entry: UsageEntry,
+        logged_cost: Option<Usd>,
+        root_profile: Option<&str>,
+    ) -> UsageEntry {
+        let profile = self
+            .pricing_provider
+            .profile_for(entry.model(), root_profile);
+        let entry = entry.with_pricing_profile(Some(profile));
+
+        let calculated_cost = match (self.cost_mode, logged_cost) {
+            (CostMode::Display, _) | (CostMode::Auto, Some(_)) => None,
+            _ => self.pricing_provider.cost_for_entry(&entry),
+        };
+
+        let cost_usd = match self.cost_mode {
+            CostMode::Calculate => calculated_cost,
+            CostMode::Display => logged_cost,
+            CostMode::Auto | CostMode::Audit => logged_cost.or(calculated_cost),
+        };
+
+        let entry = entry.with_cost(cost_usd.unwrap_or(Usd::ZERO));
+        if self.cost_mode == CostMode::Audit {
+            entry.with_audited_costs(logged_cost, calculated_cost)
+        } else {
+            entry
+        }
+    }
+
+    /// Total cache-write tokens and the 1-hour part of them. Newer payloads
+    /// split writes into `cache_creation.ephemeral_{5m,1h}_input_tokens`.
+    fn extract_cache_creation(&self, usage: &Value) -> (u64, u64) {
+        let tiers = usage.get("cache_creation");
+        let tier = |field| {
+            tiers
+                .and_then(|tiers| self.extract_u64(tiers, field).ok())
+                .unwrap_or(0)
+        };
+        let five_minute = tier("ephemeral_5m_input_tokens");
+        let one_hour = tier("ephemeral_1h_input_tokens");
+
+        let total = self
+            .extract_u64(usage, "cache_creation_input_tokens")
+            .unwrap_or(five_minute + one_hour);
+        (total.max(one_hour), one_hour)
+    }
+
+    fn with_line_metadata(&self, entry: UsageEntry, json: &Value) -> UsageEntry {
+        entry
+            .with_session_id(self.extract_string(json, "sessionId").ok())
+            .with_cwd(self.extract_string(json, "cwd").ok())
+            .with_git_branch(self.extract_string(json, "gitBranch").ok())
+            .with_version(self.extract_string(json, "version").ok())
+            .with_is_sidechain(json.get("isSidechain").and_then(|v| v.as_bool()))
+            .with_user_type(self.extract_string(json, "userType").ok())
+    }
+
+    /// Reads a "usage limit reached" notice, which Claude Code logs as a
+    /// system message or an assistant API error without usage. User and
+    /// regular assistant messages that merely quote a notice are ignored.
+    fn parse_limit_event(&self, line: &str) -> Option<LimitEvent> {
+        if !line.contains("limit") {
+            return None;
+        }
+
+        let json: Value = serde_json::from_str(line).ok()?;
+        let is_notice = match json.get("type").and_then(|v| v.as_str()) {
+            Some("system") => true,
+            Some("assistant") => {
+                json.get("isApiErrorMessage").and_then(|v| v.as_bool()) == Some(true)
+                    || json.get("error").is_some()
+            }
+            _ => false,
+        };
+        if !is_notice {
+            return None;
+        }
+
+        let timestamp = self.parse_timestamp(&json).ok()?;
+        let content = json
+            .get("message")
+            .and_then(|message| message.get("content"))
+            .or_else(|| json.get("content"))?;
+
+        let texts: Vec<&str> = match content {
+            Value::String(text) => vec![text.as_str()],
+            Value::Array(parts) => parts
+                .iter()
+                .filter_map(|part| part.get("text").and_then(|text| text.as_str()))
+                .collect(),
+            _ => Vec::new(),
+        };
+        texts
+            .into_iter()
+            .find_map(|text| LimitEvent::parse(text, timestamp))
+    }
+
     fn parse_timestamp(&self, json: &Value) -> Result<DateTime<Utc>> {
         let timestamp_str = self.extract_string(json, "timestamp")?;
 
@@ -200,9 +720,122 @@ impl Default for DataLoader {
     }
 }
 
+fn collect_jsonl_files(
+    dir_path: &Path,
+    files: &mut Vec<PathBuf>,
+    report: &mut LoadReport,
+) -> Result<()> {
+    let dir = std::fs::read_dir(dir_path)
+        .with_context(|| format!("Failed to read directory: {}", dir_path.display()))?;
+
+    for entry in dir {
+        let entry = entry.context("Failed to read directory entry")?;
+        let path = entry.path();
+
+        if path.is_file() {
+            if let Some(extension) = path.extension() {
+                if extension == "jsonl" {
+                    files.push(path);
+                }
+            }
+        } else if path.is_dir() {
+            // Recursively search subdirectories (for project directories)
+            if let Err(e) = collect_jsonl_files(&path, files, report) {
+                report.record_unreadable_file(&path, &e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Environment variable listing extra Claude config directories, separated by
+/// commas. Each directory's `projects` folder (or the directory itself, if it
+/// has none) is loaded in addition to the standard locations.
+pub const CLAUDE_CONFIG_DIR_ENV: &str = "CLAUDE_CONFIG_DIR";
+
+/// Returns every existing Claude data root: the directories listed in
+/// `CLAUDE_CONFIG_DIR` followed by `~/.claude/projects` and
+/// `~/.config/claude/projects`.
+pub fn discover_data_roots() -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from);
+    let config_dirs = std::env::var(CLAUDE_CONFIG_DIR_ENV).ok();
+
+    discover_data_roots_in(home.as_deref(), config_dirs.as_deref())
+}
+
+fn discover_data_roots_in(home: Option<&Path>, config_dirs: Option<&str>) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+
+    for dir in config_dirs.unwrap_or_default().split(',') {
+        let dir = dir.trim();
+        if dir.is_empty() {
+            continue;
+        }
+
+        let dir = PathBuf::from(dir);
+        let projects = dir.join("projects");
+        candidates.push(if projects.is_dir() { projects } else { dir });
+    }
+
+    if let Some(home) = home {
+        candidates.push(home.join(".claude").join("projects"));
+        candidates.push(home.join(".config").join("claude").join("projects"));
+    }
+
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for candidate in candidates {
+        if !candidate.is_dir() {
+            continue;
+        }
+
+        let canonical = candidate.canonicalize().unwrap_or(candidate);
+        if !roots.contains(&canonical) {
+            roots.push(canonical);
+        }
+    }
+
+    roots
+}
+
+/// Claude Code stores each project's transcripts in a folder named after the
+/// project path, so the parent directory of a transcript identifies the project.
+fn project_name(path: &Path) -> Option<String> {
+    path.parent()
+        .and_then(|parent| parent.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+#[cfg(unix)]
+fn file_identity(metadata: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.ino()
+}
+
+#[cfg(not(unix))]
+fn file_identity(_metadata: &Metadata) -> u64 {
+    0
+}
+
+/// Removes entries whose message/request id pair was already seen, keeping the
+/// first occurrence. Returns the number of entries removed.
+pub fn deduplicate_entries(entries: &mut Vec<UsageEntry>) -> usize {
+    let mut seen = HashSet::new();
+    let before = entries.len();
+    entries.retain(|entry| match entry.dedup_key() {
+        Some(key) => seen.insert(key),
+        None => true,
+    });
+    before - entries.len()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data_structures::ModelPricing;
+    use chrono::TimeZone;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -211,7 +844,7 @@ mod tests {
         let loader = DataLoader::new();
         let line = r#"{"timestamp": "2024-01-01T12:00:00Z", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50, "cache_creation_input_tokens": 0, "cache_read_input_tokens": 0}, "cost_usd": 0.001}"#;
 
-        let entry = loader.parse_line(line).unwrap();
+        let entry = loader.parse_line(line, None).unwrap();
         assert_eq!(entry.model(), "claude-3-sonnet-20240229");
         assert_eq!(entry.input_tokens(), 100);
         assert_eq!(entry.output_tokens(), 50);
@@ -223,11 +856,66 @@ mod tests {
         let loader = DataLoader::new();
         let line = r#"{"timestamp": "2024-01-01T12:00:00Z", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50, "cache_creation_input_tokens": 25, "cache_read_input_tokens": 10}, "cost_usd": 0.001}"#;
 
-        let entry = loader.parse_line(line).unwrap();
+        let entry = loader.parse_line(line, None).unwrap();
         assert_eq!(entry.cache_creation_input_tokens(), 25);
         assert_eq!(entry.cache_read_input_tokens(), 10);
     }
 
+    #[test]
+    fn test_parse_cache_write_tiers() {
+        let loader = DataLoader::new();
+        let line = r#"{"timestamp": "2024-01-01T12:00:00Z", "message": {"model": "claude-3-5-sonnet-20241022", "usage": {"input_tokens": 0, "output_tokens": 0, "cache_creation_input_tokens": 2000000, "cache_creation": {"ephemeral_5m_input_tokens": 1000000, "ephemeral_1h_input_tokens": 1000000}}}}"#;
+
+        let entry = loader.parse_line(line, None).unwrap();
+        assert_eq!(entry.cache_creation_input_tokens(), 2_000_000);
+        assert_eq!(entry.cache_creation_5m_input_tokens(), 1_000_000);
+        assert_eq!(entry.cache_creation_1h_input_tokens(), 1_000_000);
+        // $3.75/M for 5-minute writes plus $6/M for 1-hour writes
+        assert_eq!(entry.cost(), Usd::from_f64(9.75));
+    }
+
+    #[test]
+    fn test_cost_modes() {
+        let logged = r#"{"timestamp": "2024-01-01T12:00:00Z", "message": {"id": "msg_1", "model": "claude-sonnet-4-20250514", "usage": {"input_tokens": 100000, "output_tokens": 0}}, "cost_usd": 0.25}"#;
+        let unlogged = r#"{"timestamp": "2024-01-01T12:00:00Z", "model": "claude-sonnet-4-20250514", "usage": {"input_tokens": 100000, "output_tokens": 0}}"#;
+
+        let cost = |mode, line| {
+            DataLoader::new()
+                .with_cost_mode(mode)
+                .parse_line(line, None)
+                .unwrap()
+                .cost_usd()
+        };
+
+        assert_eq!(cost(CostMode::Auto, logged), 0.25);
+        assert_eq!(cost(CostMode::Auto, unlogged), 0.3);
+        assert_eq!(cost(CostMode::Calculate, logged), 0.3);
+        assert_eq!(cost(CostMode::Display, logged), 0.25);
+        assert_eq!(cost(CostMode::Display, unlogged), 0.0);
+        assert_eq!(cost(CostMode::Audit, logged), 0.25);
+    }
+
+    #[test]
+    fn test_audit_reports_discrepancies() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let content = r#"{"timestamp": "2024-01-01T12:00:00Z", "model": "claude-sonnet-4-20250514", "message_id": "msg_1", "usage": {"input_tokens": 100000, "output_tokens": 0}, "cost_usd": 0.3}
+{"timestamp": "2024-01-01T12:01:00Z", "model": "claude-sonnet-4-20250514", "message_id": "msg_2", "usage": {"input_tokens": 100000, "output_tokens": 0}, "cost_usd": 0.25}
+{"timestamp": "2024-01-01T12:02:00Z", "model": "claude-sonnet-4-20250514", "message_id": "msg_3", "usage": {"input_tokens": 100000, "output_tokens": 0}}"#;
+        temp_file.write_all(content.as_bytes()).unwrap();
+
+        let loader = DataLoader::new().with_cost_mode(CostMode::Audit);
+        let (entries, report) = loader.load_from_file_with_report(temp_file.path()).unwrap();
+
+        assert_eq!(entries[1].logged_cost_usd(), Some(0.25));
+        assert_eq!(entries[1].calculated_cost_usd(), Some(0.3));
+        assert_eq!(report.costs_audited(), 3);
+        assert_eq!(report.cost_discrepancies().len(), 1);
+
+        let discrepancy = &report.cost_discrepancies()[0];
+        assert_eq!(discrepancy.message_id(), Some("msg_2"));
+        assert_eq!(discrepancy.difference(), Usd::from_f64(-0.05));
+    }
+
     #[test]
     fn test_load_from_file() {
         let loader = DataLoader::new();
@@ -259,12 +947,360 @@ mod tests {
         assert_eq!(entries.len(), 2);
     }
 
+    #[test]
+    fn test_parse_message_and_request_ids() {
+        let loader = DataLoader::new();
+        let line = r#"{"timestamp": "2024-01-01T12:00:00Z", "requestId": "req_1", "message": {"id": "msg_1", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}}}"#;
+
+        let entry = loader.parse_line(line, None).unwrap();
+        assert_eq!(entry.message_id(), Some("msg_1"));
+        assert_eq!(entry.request_id(), Some("req_1"));
+        assert_eq!(entry.dedup_key().as_deref(), Some("msg_1:req_1"));
+    }
+
+    #[test]
+    fn test_parse_line_metadata() {
+        let loader = DataLoader::new();
+        let line = r#"{"timestamp": "2024-01-01T12:00:00Z", "sessionId": "abc-123", "cwd": "/home/me/repo", "gitBranch": "main", "version": "1.0.51", "isSidechain": false, "userType": "external", "message": {"model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}}}"#;
+
+        let entry = loader.parse_line(line, None).unwrap();
+        assert_eq!(entry.session_id(), Some("abc-123"));
+        assert_eq!(entry.cwd(), Some("/home/me/repo"));
+        assert_eq!(entry.git_branch(), Some("main"));
+        assert_eq!(entry.version(), Some("1.0.51"));
+        assert_eq!(entry.is_sidechain(), Some(false));
+        assert_eq!(entry.user_type(), Some("external"));
+        assert_eq!(entry.project(), None);
+    }
+
+    #[test]
+    fn test_load_from_file_sets_project_from_folder() {
+        let loader = DataLoader::new();
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("-home-me-repo");
+        std::fs::create_dir(&project).unwrap();
+
+        let line = r#"{"timestamp": "2024-01-01T12:00:00Z", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}}"#;
+        std::fs::write(project.join("session.jsonl"), line).unwrap();
+
+        let entries = loader
+            .load_from_file(project.join("session.jsonl"))
+            .unwrap();
+        assert_eq!(entries[0].project(), Some("-home-me-repo"));
+    }
+
+    #[test]
+    fn test_load_from_directory_removes_duplicates_across_files() {
+        let loader = DataLoader::new();
+        let dir = tempfile::tempdir().unwrap();
+
+        let line = r#"{"timestamp": "2024-01-01T12:00:00Z", "requestId": "req_1", "message": {"id": "msg_1", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}}}"#;
+        let other = r#"{"timestamp": "2024-01-01T12:05:00Z", "requestId": "req_2", "message": {"id": "msg_2", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 10, "output_tokens": 5}}}"#;
+        let no_ids = r#"{"timestamp": "2024-01-01T12:10:00Z", "message": {"model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 1, "output_tokens": 1}}}"#;
+
+        std::fs::write(
+            dir.path().join("original.jsonl"),
+            format!("{}\n{}\n{}\n", line, other, no_ids),
+        )
+        .unwrap();
+        let project = dir.path().join("project");
+        std::fs::create_dir(&project).unwrap();
+        std::fs::write(
+            project.join("resumed.jsonl"),
+            format!("{}\n{}\n", line, no_ids),
+        )
+        .unwrap();
+
+        let (entries, report) = loader.load_from_directory_with_report(dir.path()).unwrap();
+        assert_eq!(report.duplicates_removed(), 1);
+        assert_eq!(entries.len(), 4);
+        assert_eq!(
+            entries
+                .iter()
+                .filter(|e| e.message_id() == Some("msg_1"))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_load_from_roots_merges_and_tags_roots() {
+        let loader = DataLoader::new();
+        let first_root = tempfile::tempdir().unwrap();
+        let second_root = tempfile::tempdir().unwrap();
+
+        let shared = r#"{"timestamp": "2024-01-01T12:00:00Z", "requestId": "req_1", "message": {"id": "msg_1", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}}}"#;
+        let only_second = r#"{"timestamp": "2024-01-01T11:00:00Z", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 10, "output_tokens": 5}}"#;
+
+        std::fs::write(first_root.path().join("a.jsonl"), shared).unwrap();
+        std::fs::write(
+            second_root.path().join("b.jsonl"),
+            format!("{}\n{}\n", shared, only_second),
+        )
+        .unwrap();
+
+        let missing = first_root.path().join("missing");
+        let roots = [
+            first_root.path().to_path_buf(),
+            missing,
+            second_root.path().to_path_buf(),
+        ];
+        let (entries, report) = loader.load_from_roots(&roots).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(report.duplicates_removed(), 1);
+        assert_eq!(report.unreadable_files().len(), 1);
+        assert_eq!(entries[0].source_root(), Some(second_root.path()));
+        assert_eq!(entries[1].source_root(), Some(first_root.path()));
+    }
+
+    #[test]
+    fn test_root_profiles_price_entries() {
+        let mut pricing = PricingProvider::new();
+        pricing.add_profile_pricing(
+            "bedrock",
+            "claude-3-sonnet-20240229",
+            ModelPricing::new(6.0 / 1_000_000.0, 30.0 / 1_000_000.0, 0.0, 0.0),
+        );
+        let api_root = tempfile::tempdir().unwrap();
+        let bedrock_root = tempfile::tempdir().unwrap();
+        let loader = DataLoader::new()
+            .with_pricing(pricing)
+            .with_cost_mode(CostMode::Calculate)
+            .with_root_profile(bedrock_root.path(), "bedrock");
+
+        let line = r#"{"timestamp": "2024-01-01T12:00:00Z", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 1000, "output_tokens": 100}}"#;
+        std::fs::write(api_root.path().join("a.jsonl"), line).unwrap();
+        std::fs::write(bedrock_root.path().join("b.jsonl"), line).unwrap();
+
+        let roots = [api_root.path(), bedrock_root.path()];
+        let (entries, _) = loader.load_from_roots(&roots).unwrap();
+        let priced: HashMap<_, _> = entries
+            .iter()
+            .map(|entry| (entry.pricing_profile().unwrap(), entry.cost()))
+            .collect();
+
+        assert_eq!(priced["anthropic"], Usd::from_f64(0.0045));
+        assert_eq!(priced["bedrock"], Usd::from_f64(0.009));
+    }
+
+    #[test]
+    fn test_parallel_load_matches_sequential() {
+        let root = tempfile::tempdir().unwrap();
+
+        for project in 0..4 {
+            let project_dir = root.path().join(format!("project-{}", project));
+            std::fs::create_dir(&project_dir).unwrap();
+
+            for session in 0..5 {
+                let mut content = String::new();
+                for line in 0..20 {
+                    let minute = (project * 7 + session * 3 + line) % 60;
+                    content.push_str(&format!(
+                        r#"{{"timestamp": "2024-01-01T12:{:02}:00Z", "requestId": "req_{}", "message": {{"id": "msg_{}_{}", "model": "claude-3-sonnet-20240229", "usage": {{"input_tokens": {}, "output_tokens": 50}}}}}}"#,
+                        minute, line, session, line, project * 100 + line
+                    ));
+                    content.push('\n');
+                }
+                content.push_str("not json\n");
+                std::fs::write(project_dir.join(format!("{}.jsonl", session)), content).unwrap();
+            }
+        }
+
+        let (sequential, sequential_report) = DataLoader::new()
+            .load_from_directory_with_report(root.path())
+            .unwrap();
+        let (parallel, parallel_report) = DataLoader::new()
+            .with_workers(4)
+            .load_from_directory_with_report(root.path())
+            .unwrap();
+
+        assert!(!sequential.is_empty());
+        assert_eq!(
+            serde_json::to_string(&sequential).unwrap(),
+            serde_json::to_string(&parallel).unwrap()
+        );
+        assert_eq!(
+            format!("{:?}", sequential_report),
+            format!("{:?}", parallel_report)
+        );
+
+        let mut incremental = DataLoader::new().with_workers(4);
+        let update = incremental.load_incremental(root.path()).unwrap();
+        assert_eq!(
+            serde_json::to_string(&sequential).unwrap(),
+            serde_json::to_string(update.entries()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_discover_data_roots_includes_config_dirs() {
+        let home = tempfile::tempdir().unwrap();
+        let extra = tempfile::tempdir().unwrap();
+        let bare = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(home.path().join(".claude/projects")).unwrap();
+        std::fs::create_dir_all(extra.path().join("projects")).unwrap();
+
+        let config_dirs = format!(
+            "{}, {},{}",
+            extra.path().display(),
+            bare.path().display(),
+            home.path().join(".claude").display()
+        );
+        let roots = discover_data_roots_in(Some(home.path()), Some(&config_dirs));
+
+        assert_eq!(
+            roots,
+            vec![
+                extra.path().join("projects").canonicalize().unwrap(),
+                bare.path().canonicalize().unwrap(),
+                home.path().join(".claude/projects").canonicalize().unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_incremental_reads_only_appended_lines() {
+        let mut loader = DataLoader::new();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+
+        let first = r#"{"timestamp": "2024-01-01T12:00:00Z", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}}"#;
+        let second = r#"{"timestamp": "2024-01-01T12:05:00Z", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 200, "output_tokens": 100}}"#;
+        let (head, tail) = second.split_at(40);
+
+        std::fs::write(&path, format!("{}\n", first)).unwrap();
+        let update = loader.load_incremental(dir.path()).unwrap();
+        assert_eq!(update.entries().len(), 1);
+        assert!(!update.rewritten());
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(head.as_bytes()).unwrap();
+        let update = loader.load_incremental(dir.path()).unwrap();
+        assert!(update.entries().is_empty());
+
+        file.write_all(format!("{}\n", tail).as_bytes()).unwrap();
+        let update = loader.load_incremental(dir.path()).unwrap();
+        assert_eq!(update.entries().len(), 1);
+        assert_eq!(update.entries()[0].input_tokens(), 200);
+
+        let update = loader.load_incremental(dir.path()).unwrap();
+        assert!(update.entries().is_empty());
+    }
+
+    #[test]
+    fn test_load_incremental_detects_truncated_file() {
+        let mut loader = DataLoader::new();
+        let mut temp_file = NamedTempFile::new().unwrap();
+
+        let line = r#"{"timestamp": "2024-01-01T12:00:00Z", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}}"#;
+        writeln!(temp_file, "{}", line).unwrap();
+        writeln!(temp_file, "{}", line).unwrap();
+        loader.load_incremental(temp_file.path()).unwrap();
+
+        std::fs::write(temp_file.path(), format!("{}\n", line)).unwrap();
+        let update = loader.load_incremental(temp_file.path()).unwrap();
+        assert!(update.rewritten());
+    }
+
+    #[test]
+    fn test_load_report_counts_skipped_lines() {
+        let loader = DataLoader::new();
+        let mut temp_file = NamedTempFile::new().unwrap();
+
+        let content = r#"{"timestamp": "2024-01-01T12:00:00Z", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}}
+{"timestamp": "2024-01-01T12:00:00Z", "model": "claude-mystery", "usage": {"input_tokens": 100, "output_tokens": 50}}
+{"timestamp": "2024-01-01T12:01:00Z", "model": "claude-mystery", "usage": {"input_tokens": 100, "output_tokens": 50}, "cost_usd": 0.002}
+{"invalid": "json"
+{"type": "user", "timestamp": "2024-01-01T12:00:00Z", "message": {"role": "user"}}
+{"timestamp": "yesterday", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}}
+
+"#;
+        temp_file.write_all(content.as_bytes()).unwrap();
+
+        let (entries, report) = loader.load_from_file_with_report(temp_file.path()).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(report.files_scanned(), 1);
+        assert_eq!(report.lines_read(), 6);
+        assert_eq!(report.skipped_count(SkipReason::InvalidJson), 1);
+        assert_eq!(report.skipped_count(SkipReason::MissingUsage), 1);
+        assert_eq!(report.skipped_count(SkipReason::InvalidTimestamp), 1);
+        assert_eq!(report.total_skipped(), 3);
+        assert!(report.unknown_models().contains("claude-mystery"));
+        assert!(!report.unknown_models().contains("claude-3-sonnet-20240229"));
+        assert_eq!(report.unpriced_entries(), 1);
+    }
+
+    #[test]
+    fn test_load_report_resolves_pricing_at_entry_time() {
+        let mut pricing = PricingProvider::new();
+        pricing.add_pricing(
+            "claude-retired-20230101",
+            ModelPricing::new(1.0 / 1_000_000.0, 5.0 / 1_000_000.0, 0.0, 0.0)
+                .with_effective_period(
+                    None,
+                    Some(Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap()),
+                ),
+        );
+        let loader = DataLoader::new().with_pricing(pricing);
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(
+            temp_file,
+            r#"{{"timestamp": "2024-01-01T12:00:00Z", "model": "claude-retired-20230101", "usage": {{"input_tokens": 100, "output_tokens": 50}}}}"#
+        )
+        .unwrap();
+
+        let (_, report) = loader.load_from_file_with_report(temp_file.path()).unwrap();
+        assert!(report.unknown_models().is_empty());
+        assert_eq!(report.unpriced_entries(), 0);
+    }
+
+    #[test]
+    fn test_load_report_collects_limit_events() {
+        let loader = DataLoader::new();
+        let mut temp_file = NamedTempFile::new().unwrap();
+
+        let content = r#"{"type": "assistant", "timestamp": "2025-01-15T14:30:00Z", "isApiErrorMessage": true, "message": {"role": "assistant", "content": [{"type": "text", "text": "Claude AI usage limit reached|1736960400"}]}}
+{"type": "system", "timestamp": "2025-01-15T14:31:00Z", "content": "Claude usage limit reached. Your limit will reset at 5pm."}
+{"type": "user", "timestamp": "2025-01-15T14:32:00Z", "message": {"role": "user", "content": "what is the rate limit here?"}}
+"#;
+        temp_file.write_all(content.as_bytes()).unwrap();
+
+        let (entries, report) = loader.load_from_file_with_report(temp_file.path()).unwrap();
+        assert!(entries.is_empty());
+        assert_eq!(report.limit_events().len(), 2);
+        assert_eq!(
+            report.limit_events()[0].resets_at(),
+            Some(Utc.with_ymd_and_hms(2025, 1, 15, 17, 0, 0).unwrap())
+        );
+        assert!(report.limit_events()[1].resets_at().is_some());
+        assert_eq!(report.total_skipped(), 1);
+    }
+
+    #[test]
+    fn test_limit_events_ignore_quoted_notices() {
+        let loader = DataLoader::new();
+        let mut temp_file = NamedTempFile::new().unwrap();
+
+        let content = r#"{"type": "user", "timestamp": "2025-01-15T14:30:00Z", "message": {"role": "user", "content": "Usage limit reached - what happens when my usage limit is reached?"}}
+{"type": "assistant", "timestamp": "2025-01-15T14:31:00Z", "message": {"role": "assistant", "content": [{"type": "text", "text": "Claude AI usage limit reached|1736960400 is the notice you will see."}]}}
+"#;
+        temp_file.write_all(content.as_bytes()).unwrap();
+
+        let (_, report) = loader.load_from_file_with_report(temp_file.path()).unwrap();
+        assert!(report.limit_events().is_empty());
+        assert_eq!(report.total_skipped(), 2);
+    }
+
     #[test]
     fn test_invalid_json_line() {
         let loader = DataLoader::new();
         let line = r#"{"invalid": "json"#;
 
-        assert!(loader.parse_line(line).is_err());
+        assert!(loader.parse_line(line, None).is_err());
     }
 
     #[test]
@@ -272,6 +1308,6 @@ mod tests {
         let loader = DataLoader::new();
         let line = r#"{"timestamp": "2024-01-01T12:00:00Z"}"#;
 
-        assert!(loader.parse_line(line).is_err());
+        assert!(loader.parse_line(line, None).is_err());
     }
 }
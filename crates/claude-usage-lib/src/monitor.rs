@@ -1,11 +1,20 @@
+use crate::archive::UsageArchive;
 use crate::calculator::Calculator;
-use crate::data_structures::{BurnRate, ClaudePlan, SessionBlock, UsageEntry, UsageProjection};
-use crate::identifier::SessionIdentifier;
+use crate::cost::{CostMode, Usd};
+use crate::currency::Currency;
+use crate::data_structures::{
+    BurnRate, ClaudePlan, LimitEstimate, ProjectUsage, SessionBlock, UsageEntry, UsageProjection,
+};
+use crate::identifier::{SessionConfig, SessionIdentifier};
+use crate::limits::{LimitEvent, LimitModel, WeightedTokens};
 use crate::loader::DataLoader;
-use crate::pricing::PricingProvider;
+use crate::pricing::{PricingProvider, DEFAULT_PROFILE};
+use crate::report::LoadReport;
+use crate::weekly::{WeeklyBudget, WeeklyConfig, WeeklyUsage};
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 pub struct UsageMonitor {
     usage_entries: Vec<UsageEntry>,
@@ -14,6 +23,12 @@ pub struct UsageMonitor {
     calculator: Calculator,
     identifier: SessionIdentifier,
     loader: DataLoader,
+    load_report: LoadReport,
+    archive: Option<UsageArchive>,
+    /// Roots the in-memory view of the archive was last built for.
+    archive_scope: Option<Vec<PathBuf>>,
+    limit_model: Box<dyn LimitModel>,
+    weekly_config: WeeklyConfig,
 }
 
 impl UsageMonitor {
@@ -25,25 +40,229 @@ impl UsageMonitor {
             calculator: Calculator::new(),
             identifier: SessionIdentifier::new(),
             loader: DataLoader::new(),
+            load_report: LoadReport::new(),
+            archive: None,
+            archive_scope: None,
+            limit_model: Box::new(WeightedTokens),
+            weekly_config: WeeklyConfig::default(),
         }
     }
 
-    pub fn load_data<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
-        self.usage_entries = self.loader.load_from_file(path)?;
+    /// Parses transcripts on `workers` threads when loading.
+    pub fn with_load_workers(mut self, workers: usize) -> Self {
+        self.loader.set_workers(workers);
+        self
+    }
+
+    /// Tracks usage against weekly budgets as well as per session.
+    pub fn with_weekly_config(mut self, config: WeeklyConfig) -> Self {
+        self.weekly_config = config;
+        self
+    }
+
+    pub fn weekly_config(&self) -> &WeeklyConfig {
+        &self.weekly_config
+    }
+
+    /// Groups entries into sessions using `config`.
+    pub fn with_session_config(mut self, config: SessionConfig) -> Self {
+        self.identifier = SessionIdentifier::with_config(config);
         self.recalculate_blocks();
-        Ok(())
+        self
+    }
+
+    pub fn session_config(&self) -> &SessionConfig {
+        self.identifier.config()
+    }
+
+    /// Prices the entries loaded from `root` with the pricing profile
+    /// `profile`.
+    pub fn with_root_profile<P: AsRef<Path>>(mut self, root: P, profile: &str) -> Self {
+        self.loader.set_root_profile(root, profile);
+        self
+    }
+
+    /// Chooses between logged and computed costs when loading.
+    pub fn with_cost_mode(mut self, cost_mode: CostMode) -> Self {
+        self.loader.set_cost_mode(cost_mode);
+        self
+    }
+
+    /// Tolerance, in dollars, for cost discrepancies in [`CostMode::Audit`].
+    pub fn with_audit_tolerance(mut self, tolerance: f64) -> Self {
+        self.loader.set_audit_tolerance(tolerance);
+        self
+    }
+
+    pub fn cost_mode(&self) -> CostMode {
+        self.loader.cost_mode()
+    }
+
+    /// Uses `pricing_provider` for weights, costs and parsing.
+    pub fn with_pricing(mut self, pricing_provider: PricingProvider) -> Self {
+        self.loader.set_pricing(pricing_provider.clone());
+        self.pricing_provider = pricing_provider;
+        self.recalculate_blocks();
+        self
+    }
+
+    pub fn pricing_provider(&self) -> &PricingProvider {
+        &self.pricing_provider
+    }
+
+    /// Measures usage against plan limits with `limit_model` instead of
+    /// [`WeightedTokens`].
+    pub fn with_limit_model(mut self, limit_model: Box<dyn LimitModel>) -> Self {
+        self.limit_model = limit_model;
+        self
+    }
+
+    pub fn limit_model(&self) -> &dyn LimitModel {
+        self.limit_model.as_ref()
+    }
+
+    /// Quota units used by `block` under the current limit model.
+    pub fn block_units(&self, block: &SessionBlock) -> f64 {
+        self.limit_model.block_units(block, &self.pricing_provider)
+    }
+
+    /// Keeps every loaded entry in `archive` and reports the archived history
+    /// alongside the live logs, so entries survive transcript cleanup. Until
+    /// the first load the whole archive is shown; after that, only entries
+    /// archived from the paths being loaded.
+    pub fn with_archive(mut self, archive: UsageArchive) -> Self {
+        self.usage_entries = archive.entries().to_vec();
+        self.archive = Some(archive);
+        self.archive_scope = None;
+        self.recalculate_blocks();
+        self
+    }
+
+    pub fn archive(&self) -> Option<&UsageArchive> {
+        self.archive.as_ref()
+    }
+
+    pub fn load_data<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let (entries, report) = self.loader.load_from_file_with_report(&path)?;
+        self.replace_entries(entries, report, &[path])
     }
 
     pub fn load_directory<P: AsRef<Path>>(&mut self, dir_path: P) -> Result<()> {
-        self.usage_entries = self.loader.load_from_directory(dir_path)?;
+        let (entries, report) = self.loader.load_from_directory_with_report(&dir_path)?;
+        self.replace_entries(entries, report, &[dir_path])
+    }
+
+    /// Loads and merges several data roots, e.g. those returned by
+    /// [`crate::discover_data_roots`].
+    pub fn load_roots<P: AsRef<Path>>(&mut self, roots: &[P]) -> Result<()> {
+        let (entries, report) = self.loader.load_from_roots(roots)?;
+        self.replace_entries(entries, report, roots)
+    }
+
+    /// Loads entries appended to `path` since the last refresh and merges them
+    /// into the existing blocks. Falls back to a full reload when a file was
+    /// truncated or replaced.
+    pub fn refresh<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        self.refresh_roots(&[path])
+    }
+
+    /// Incremental counterpart of [`UsageMonitor::load_roots`].
+    pub fn refresh_roots<P: AsRef<Path>>(&mut self, roots: &[P]) -> Result<()> {
+        let update = self.loader.load_incremental_roots(roots)?;
+
+        if update.rewritten() {
+            self.loader.reset_incremental_state();
+            let (entries, report) = self.loader.load_incremental_roots(roots)?.into_parts();
+            return self.replace_entries(entries, report, roots);
+        }
+
+        let (entries, report) = update.into_parts();
+
+        if let Some(archive) = self.archive.as_mut() {
+            archive.record(&entries)?;
+            self.load_report.merge(report);
+
+            // The view already holds the archive for these roots, so only the
+            // appended entries are new; otherwise rebuild it for them.
+            let scope = archive_scope(roots);
+            if self.archive_scope.as_ref() == Some(&scope) {
+                self.merge_entries(entries);
+            } else {
+                self.usage_entries = archive.history_for(&scope, &entries);
+                self.archive_scope = Some(scope);
+                self.recalculate_blocks();
+            }
+            if self.sync_limit_events() {
+                self.recalculate_blocks();
+            }
+            return Ok(());
+        }
+
+        self.load_report.merge(report);
+        self.merge_entries(entries);
+        if self.sync_limit_events() {
+            self.recalculate_blocks();
+        }
+        Ok(())
+    }
+
+    /// Passes the logged limit events on to the identifier. Returns true if
+    /// they changed, in which case the blocks must be recalculated.
+    fn sync_limit_events(&mut self) -> bool {
+        if self.identifier.limit_events() == self.load_report.limit_events() {
+            return false;
+        }
+        self.identifier
+            .set_limit_events(self.load_report.limit_events().to_vec());
+        true
+    }
+
+    fn replace_entries<P: AsRef<Path>>(
+        &mut self,
+        entries: Vec<UsageEntry>,
+        report: LoadReport,
+        roots: &[P],
+    ) -> Result<()> {
+        self.usage_entries = match self.archive.as_mut() {
+            Some(archive) => {
+                archive.record(&entries)?;
+                let scope = archive_scope(roots);
+                let history = archive.history_for(&scope, &entries);
+                self.archive_scope = Some(scope);
+                history
+            }
+            None => entries,
+        };
+        self.load_report = report;
+        self.sync_limit_events();
         self.recalculate_blocks();
         Ok(())
     }
 
+    fn merge_entries(&mut self, new_entries: Vec<UsageEntry>) {
+        let Some(first_new) = new_entries.first() else {
+            return;
+        };
+
+        let in_order = self
+            .usage_entries
+            .last()
+            .is_none_or(|last| first_new.timestamp() >= last.timestamp());
+
+        if in_order {
+            self.identifier
+                .extend_blocks(&mut self.session_blocks, &new_entries);
+            self.usage_entries.extend(new_entries);
+        } else {
+            self.usage_entries.extend(new_entries);
+            self.usage_entries.sort_by_key(|entry| entry.timestamp());
+            self.recalculate_blocks();
+        }
+    }
+
     pub fn add_entry(&mut self, entry: UsageEntry) {
         self.usage_entries.push(entry);
-        self.usage_entries
-            .sort_by(|a, b| a.timestamp().cmp(&b.timestamp()));
+        self.usage_entries.sort_by_key(|entry| entry.timestamp());
         self.recalculate_blocks();
     }
 
@@ -57,19 +276,19 @@ impl UsageMonitor {
 
     pub fn get_current_burn_rate(&self) -> Option<BurnRate> {
         self.session_blocks.last().and_then(|block| {
-            self.calculator
-                .calculate_weighted_burn_rate_with_current_time(
-                    block,
-                    &self.pricing_provider,
-                    chrono::Utc::now(),
-                )
+            self.calculator.calculate_unit_burn_rate(
+                block,
+                self.block_units(block),
+                chrono::Utc::now(),
+            )
         })
     }
 
     pub fn get_burn_rate_for_block(&self, block_index: usize) -> Option<BurnRate> {
-        self.session_blocks
-            .get(block_index)
-            .and_then(|block| self.calculator.calculate_burn_rate(block))
+        self.session_blocks.get(block_index).and_then(|block| {
+            self.calculator
+                .calculate_block_unit_burn_rate(block, self.block_units(block))
+        })
     }
 
     pub fn project_usage(
@@ -91,11 +310,10 @@ impl UsageMonitor {
     }
 
     pub fn calculate_hourly_burn_rate(&self, current_time: DateTime<Utc>) -> f64 {
-        self.calculator.calculate_weighted_hourly_burn_rate(
-            &self.session_blocks,
-            current_time,
-            &self.pricing_provider,
-        )
+        self.calculator
+            .calculate_hourly_unit_rate(&self.session_blocks, current_time, |block| {
+                self.block_units(block)
+            })
     }
 
     pub fn calculate_tokens_per_second(&self, current_time: DateTime<Utc>) -> f64 {
@@ -103,21 +321,31 @@ impl UsageMonitor {
     }
 
     pub fn get_total_cost(&self) -> f64 {
+        self.get_total_usd().as_f64()
+    }
+
+    pub fn get_total_usd(&self) -> Usd {
         self.calculator.calculate_total_cost(&self.session_blocks)
     }
 
+    /// Total cost in `currency`, each day's spend converted at that day's rate.
+    pub fn get_total_cost_in(&self, currency: &Currency) -> Option<f64> {
+        let entries = self.session_blocks.iter().flat_map(|block| block.entries());
+        Self::convert_entries(entries, currency)
+    }
+
     pub fn get_total_tokens(&self) -> u64 {
         self.calculator.calculate_total_tokens(&self.session_blocks)
     }
 
     pub fn get_average_burn_rate(&self) -> Option<BurnRate> {
         self.calculator
-            .calculate_average_burn_rate(&self.session_blocks)
+            .calculate_average_unit_burn_rate(&self.session_blocks, |block| self.block_units(block))
     }
 
     pub fn get_peak_burn_rate(&self) -> Option<BurnRate> {
         self.calculator
-            .calculate_peak_burn_rate(&self.session_blocks)
+            .calculate_peak_unit_burn_rate(&self.session_blocks, |block| self.block_units(block))
     }
 
     pub fn get_active_sessions(&self, current_time: DateTime<Utc>) -> Vec<&SessionBlock> {
@@ -145,39 +373,96 @@ impl UsageMonitor {
     }
 
     pub fn get_model_breakdown(&self) -> std::collections::HashMap<String, (u64, f64)> {
-        let mut breakdown = std::collections::HashMap::new();
+        self.model_breakdown(&self.usage_entries)
+    }
 
-        for entry in &self.usage_entries {
+    fn model_breakdown(&self, entries: &[UsageEntry]) -> HashMap<String, (u64, f64)> {
+        let mut breakdown = HashMap::new();
+
+        for entry in entries {
             let stats = breakdown
                 .entry(entry.model().to_string())
-                .or_insert((0, 0.0));
+                .or_insert((0, Usd::ZERO));
             stats.0 += entry.total_tokens();
-            stats.1 += entry.cost_usd();
+            stats.1 += entry.cost();
         }
 
         breakdown
+            .into_iter()
+            .map(|(model, (tokens, cost))| (model, (tokens, cost.as_f64())))
+            .collect()
+    }
+
+    /// Total cost per pricing profile across all loaded entries.
+    pub fn get_profile_breakdown(&self) -> HashMap<String, f64> {
+        let mut breakdown: HashMap<String, Usd> = HashMap::new();
+        for entry in &self.usage_entries {
+            let profile = entry.pricing_profile().unwrap_or(DEFAULT_PROFILE);
+            *breakdown.entry(profile.to_string()).or_default() += entry.cost();
+        }
+
+        breakdown
+            .into_iter()
+            .map(|(profile, cost)| (profile, cost.as_f64()))
+            .collect()
     }
 
+    /// Cost of the entries priced with `profile`, in `currency`, each day's
+    /// spend converted at that day's rate.
+    pub fn get_profile_cost_in(&self, profile: &str, currency: &Currency) -> Option<f64> {
+        let entries = self
+            .usage_entries
+            .iter()
+            .filter(|entry| entry.pricing_profile().unwrap_or(DEFAULT_PROFILE) == profile);
+        Self::convert_entries(entries, currency)
+    }
+
+    /// Usage grouped by project across all loaded entries. Entries are keyed
+    /// by their project folder, falling back to the working directory.
+    pub fn get_project_breakdown(&self) -> HashMap<String, ProjectUsage> {
+        self.project_breakdown(&self.usage_entries)
+    }
+
+    pub fn get_current_block_project_breakdown(&self) -> HashMap<String, ProjectUsage> {
+        self.session_blocks
+            .last()
+            .map(|block| self.project_breakdown(block.entries()))
+            .unwrap_or_default()
+    }
+
+    fn project_breakdown(&self, entries: &[UsageEntry]) -> HashMap<String, ProjectUsage> {
+        let mut breakdown: HashMap<String, ProjectUsage> = HashMap::new();
+
+        for entry in entries {
+            let project = entry
+                .project()
+                .or(entry.cwd())
+                .unwrap_or("unknown")
+                .to_string();
+            let units = self.limit_model.units(entry, &self.pricing_provider);
+            breakdown
+                .entry(project)
+                .or_insert_with(|| ProjectUsage::new(entry.timestamp()))
+                .add_entry(entry, units);
+        }
+
+        breakdown
+    }
+
+    /// Quota units used by `model` under the current limit model.
     pub fn get_weighted_tokens(&self, model: &str) -> f64 {
-        let model_weight = self.pricing_provider.get_model_weight(model);
         self.usage_entries
             .iter()
             .filter(|entry| entry.model() == model)
-            .map(|entry| {
-                self.calculator
-                    .calculate_weighted_tokens(entry, model_weight)
-            })
+            .map(|entry| self.limit_model.units(entry, &self.pricing_provider))
             .sum()
     }
 
+    /// Quota units used by all entries under the current limit model.
     pub fn get_total_weighted_tokens(&self) -> f64 {
         self.usage_entries
             .iter()
-            .map(|entry| {
-                let model_weight = self.pricing_provider.get_model_weight(entry.model());
-                self.calculator
-                    .calculate_weighted_tokens(entry, model_weight)
-            })
+            .map(|entry| self.limit_model.units(entry, &self.pricing_provider))
             .sum()
     }
 
@@ -189,24 +474,61 @@ impl UsageMonitor {
             .calculate_time_to_limit(current_tokens, token_limit, current_burn_rate)
     }
 
-    pub fn estimate_time_to_plan_limit(&self, plan: ClaudePlan) -> Option<chrono::Duration> {
-        self.estimate_time_to_limit(plan.max_tokens())
+    pub fn estimate_time_to_plan_limit(&self, plan: &ClaudePlan) -> Option<chrono::Duration> {
+        self.estimate_time_to_limit(self.get_token_limit(plan) as u64)
+    }
+
+    /// The per-block limit for `plan`: the estimate from history for
+    /// `ClaudePlan::Auto` (or its fallback without history), the plan's
+    /// fixed limit otherwise.
+    pub fn get_token_limit(&self, plan: &ClaudePlan) -> f64 {
+        match plan {
+            ClaudePlan::Auto => self
+                .estimate_token_limit()
+                .map_or(plan.max_tokens() as f64, |estimate| estimate.limit()),
+            _ => plan.max_tokens() as f64,
+        }
+    }
+
+    pub fn estimate_token_limit(&self) -> Option<LimitEstimate> {
+        self.estimate_token_limit_at(chrono::Utc::now())
+    }
+
+    /// Estimates the per-block limit from blocks that hit a usage limit, or
+    /// from the largest completed block when none has. The block still in
+    /// progress at `current_time` is left out so it never measures itself.
+    pub fn estimate_token_limit_at(&self, current_time: DateTime<Utc>) -> Option<LimitEstimate> {
+        let hits: Vec<f64> = self
+            .get_limit_hits()
+            .into_iter()
+            .map(|(_, units)| units)
+            .collect();
+        let completed = match self.session_blocks.last() {
+            Some(last) if current_time < last.end_time() => self.session_blocks.len() - 1,
+            _ => self.session_blocks.len(),
+        };
+        let blocks: Vec<f64> = self.session_blocks[..completed]
+            .iter()
+            .map(|block| self.block_units(block))
+            .collect();
+
+        self.calculator.estimate_token_limit(&hits, &blocks)
     }
 
-    pub fn get_plan_usage_percentage(&self, plan: ClaudePlan) -> f64 {
+    pub fn get_plan_usage_percentage(&self, plan: &ClaudePlan) -> f64 {
         let current_tokens = self.get_total_weighted_tokens();
-        (current_tokens / plan.max_tokens() as f64) * 100.0
+        (current_tokens / self.get_token_limit(plan)) * 100.0
     }
 
     pub fn get_current_session_tokens(&self) -> f64 {
-        self.session_blocks.last().map_or(0.0, |block| {
-            block.calculate_weighted_tokens(&self.pricing_provider)
-        })
+        self.session_blocks
+            .last()
+            .map_or(0.0, |block| self.block_units(block))
     }
 
-    pub fn get_current_session_percentage(&self, plan: ClaudePlan) -> f64 {
+    pub fn get_current_session_percentage(&self, plan: &ClaudePlan) -> f64 {
         let current_tokens = self.get_current_session_tokens();
-        (current_tokens / plan.max_tokens() as f64) * 100.0
+        (current_tokens / self.get_token_limit(plan)) * 100.0
     }
 
     pub fn get_current_session_info(&self) -> (usize, usize) {
@@ -222,31 +544,84 @@ impl UsageMonitor {
     pub fn get_current_block_tokens(&self) -> f64 {
         self.session_blocks
             .last()
-            .map(|block| block.calculate_weighted_tokens(&self.pricing_provider))
+            .map(|block| self.block_units(block))
             .unwrap_or(0.0)
     }
 
-    pub fn get_current_block_percentage(&self, plan: ClaudePlan) -> f64 {
+    /// Blocks where a usage limit was hit, with the limit-model units used in
+    /// the block up to the notice.
+    pub fn get_limit_hits(&self) -> Vec<(&LimitEvent, f64)> {
+        self.session_blocks
+            .iter()
+            .filter_map(|block| {
+                let event = block.limit_event()?;
+                let units = block
+                    .entries()
+                    .iter()
+                    .filter(|entry| entry.timestamp() <= event.timestamp())
+                    .map(|entry| self.limit_model.units(entry, &self.pricing_provider))
+                    .sum();
+                Some((event, units))
+            })
+            .collect()
+    }
+
+    pub fn get_weekly_usage(&self) -> WeeklyUsage {
+        self.get_weekly_usage_at(chrono::Utc::now())
+    }
+
+    /// Usage in limit-model units for the week containing `current_time`,
+    /// all models and Opus only, up to `current_time`.
+    pub fn get_weekly_usage_at(&self, current_time: DateTime<Utc>) -> WeeklyUsage {
+        let (window_start, resets_at) = self.weekly_config.window_for(current_time);
+
+        let mut all_models = 0.0;
+        let mut opus = 0.0;
+        for entry in self
+            .usage_entries
+            .iter()
+            .filter(|entry| entry.timestamp() >= window_start && entry.timestamp() <= current_time)
+        {
+            let units = self.limit_model.units(entry, &self.pricing_provider);
+            all_models += units;
+            if entry.model().to_ascii_lowercase().contains("opus") {
+                opus += units;
+            }
+        }
+
+        let budget = |used: f64, budget: Option<f64>| {
+            let exhaustion = budget.and_then(|budget| {
+                self.calculator.project_exhaustion(
+                    used,
+                    budget,
+                    window_start,
+                    resets_at,
+                    current_time,
+                )
+            });
+            WeeklyBudget::new(used, budget, exhaustion)
+        };
+
+        WeeklyUsage::new(
+            window_start,
+            resets_at,
+            budget(all_models, self.weekly_config.all_models_budget()),
+            budget(opus, self.weekly_config.opus_budget()),
+        )
+    }
+
+    pub fn get_current_block_percentage(&self, plan: &ClaudePlan) -> f64 {
         let current_tokens = self.get_current_block_tokens();
-        (current_tokens / plan.max_tokens() as f64) * 100.0
+        (current_tokens / self.get_token_limit(plan)) * 100.0
     }
 
     pub fn get_current_block_model_breakdown(
         &self,
     ) -> std::collections::HashMap<String, (u64, f64)> {
-        let mut breakdown = std::collections::HashMap::new();
-
-        if let Some(current_block) = self.session_blocks.last() {
-            for entry in current_block.entries() {
-                let stats = breakdown
-                    .entry(entry.model().to_string())
-                    .or_insert((0, 0.0));
-                stats.0 += entry.total_tokens();
-                stats.1 += entry.cost_usd();
-            }
-        }
-
-        breakdown
+        self.session_blocks
+            .last()
+            .map(|block| self.model_breakdown(block.entries()))
+            .unwrap_or_default()
     }
 
     pub fn get_current_block_cost(&self) -> f64 {
@@ -256,6 +631,37 @@ impl UsageMonitor {
             .unwrap_or(0.0)
     }
 
+    /// Current block cost as a share of the plan's cost cap, or `None` if
+    /// the plan has no cap.
+    pub fn get_current_block_cost_percentage(&self, plan: &ClaudePlan) -> Option<f64> {
+        let cap = plan.cost_cap().filter(|cap| cap.micros() > 0)?;
+        Some(self.get_current_block_cost() / cap.as_f64() * 100.0)
+    }
+
+    pub fn get_current_block_cost_in(&self, currency: &Currency) -> Option<f64> {
+        let entries = self
+            .session_blocks
+            .last()
+            .into_iter()
+            .flat_map(|block| block.entries());
+        Self::convert_entries(entries, currency)
+    }
+
+    fn convert_entries<'a>(
+        entries: impl Iterator<Item = &'a UsageEntry>,
+        currency: &Currency,
+    ) -> Option<f64> {
+        let mut daily: std::collections::BTreeMap<_, Usd> = std::collections::BTreeMap::new();
+        for entry in entries {
+            *daily.entry(entry.timestamp().date_naive()).or_default() += entry.cost();
+        }
+
+        daily
+            .into_iter()
+            .map(|(date, cost)| currency.rate_at(date).map(|rate| cost.as_f64() * rate))
+            .sum()
+    }
+
     pub fn get_current_block_duration(&self) -> f64 {
         self.session_blocks
             .last()
@@ -278,18 +684,34 @@ impl UsageMonitor {
         output_tokens: u64,
     ) -> Option<f64> {
         self.pricing_provider
-            .calculate_cost(model, input_tokens, output_tokens, 0, 0)
+            .calculate_cost(model, input_tokens, output_tokens, 0, 0, 0)
+            .map(|cost| cost.as_f64())
     }
 
     pub fn clear_data(&mut self) {
         self.usage_entries.clear();
         self.session_blocks.clear();
+        self.load_report = LoadReport::new();
+        self.sync_limit_events();
+        self.loader.reset_incremental_state();
+        self.archive_scope = None;
     }
 
     pub fn session_count(&self) -> usize {
         self.session_blocks.len()
     }
 
+    /// Number of duplicate entries dropped while loading.
+    pub fn duplicate_count(&self) -> usize {
+        self.load_report.duplicates_removed()
+    }
+
+    /// Diagnostics for the data currently loaded. Incremental refreshes are
+    /// merged into the report of the initial load.
+    pub fn load_report(&self) -> &LoadReport {
+        &self.load_report
+    }
+
     pub fn entry_count(&self) -> usize {
         self.usage_entries.len()
     }
@@ -303,6 +725,13 @@ impl UsageMonitor {
     }
 }
 
+fn archive_scope<P: AsRef<Path>>(roots: &[P]) -> Vec<PathBuf> {
+    roots
+        .iter()
+        .map(|root| root.as_ref().to_path_buf())
+        .collect()
+}
+
 impl Default for UsageMonitor {
     fn default() -> Self {
         Self::new()
@@ -357,6 +786,224 @@ mod tests {
         assert_eq!(monitor.session_count(), 1);
     }
 
+    #[test]
+    fn test_refresh_merges_appended_entries() {
+        let mut monitor = UsageMonitor::new();
+        let mut temp_file = NamedTempFile::new().unwrap();
+
+        let first = r#"{"timestamp": "2024-01-01T12:00:00Z", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}, "cost_usd": 0.001}"#;
+        let second = r#"{"timestamp": "2024-01-01T13:00:00Z", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}, "cost_usd": 0.001}"#;
+        let later = r#"{"timestamp": "2024-01-01T20:00:00Z", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}, "cost_usd": 0.001}"#;
+
+        writeln!(temp_file, "{}", first).unwrap();
+        monitor.refresh(temp_file.path()).unwrap();
+        assert_eq!(monitor.entry_count(), 1);
+
+        writeln!(temp_file, "{}", second).unwrap();
+        writeln!(temp_file, "{}", later).unwrap();
+        monitor.refresh(temp_file.path()).unwrap();
+        assert_eq!(monitor.entry_count(), 3);
+        assert_eq!(monitor.session_count(), 2);
+        assert_eq!(monitor.get_session_blocks()[0].entries().len(), 2);
+    }
+
+    #[test]
+    fn test_archive_keeps_entries_after_log_cleanup() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("archive.jsonl");
+        let logs = dir.path().join("projects");
+        std::fs::create_dir(&logs).unwrap();
+
+        let old = r#"{"timestamp": "2024-01-01T12:00:00Z", "message": {"id": "msg_1", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}}, "requestId": "req_1", "cost_usd": 0.001}"#;
+        let new = r#"{"timestamp": "2024-01-02T12:00:00Z", "message": {"id": "msg_2", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}}, "requestId": "req_2", "cost_usd": 0.001}"#;
+
+        std::fs::write(logs.join("old.jsonl"), format!("{}\n", old)).unwrap();
+        let mut monitor =
+            UsageMonitor::new().with_archive(UsageArchive::open(&archive_path).unwrap());
+        monitor.load_directory(&logs).unwrap();
+        assert_eq!(monitor.entry_count(), 1);
+
+        std::fs::remove_file(logs.join("old.jsonl")).unwrap();
+        std::fs::write(logs.join("new.jsonl"), format!("{}\n", new)).unwrap();
+
+        let mut monitor =
+            UsageMonitor::new().with_archive(UsageArchive::open(&archive_path).unwrap());
+        assert_eq!(monitor.entry_count(), 1);
+        monitor.refresh(&logs).unwrap();
+        assert_eq!(monitor.entry_count(), 2);
+        assert_eq!(monitor.archive().unwrap().len(), 2);
+
+        monitor.clear_data();
+        monitor.refresh(&logs).unwrap();
+        assert_eq!(monitor.entry_count(), 2);
+    }
+
+    #[test]
+    fn test_scoped_load_ignores_entries_archived_from_other_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("archive.jsonl");
+        let work = dir.path().join("work");
+        let personal = dir.path().join("personal");
+        std::fs::create_dir(&work).unwrap();
+        std::fs::create_dir(&personal).unwrap();
+
+        let work_line = r#"{"timestamp": "2024-01-01T12:00:00Z", "message": {"id": "msg_1", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}}, "requestId": "req_1"}"#;
+        let personal_line = r#"{"timestamp": "2024-01-01T13:00:00Z", "message": {"id": "msg_2", "model": "claude-3-sonnet-20240229", "usage": {"input_tokens": 100, "output_tokens": 50}}, "requestId": "req_2"}"#;
+        std::fs::write(work.join("a.jsonl"), format!("{}\n", work_line)).unwrap();
+        std::fs::write(personal.join("b.jsonl"), format!("{}\n", personal_line)).unwrap();
+
+        let mut monitor =
+            UsageMonitor::new().with_archive(UsageArchive::open(&archive_path).unwrap());
+        monitor.load_roots(&[&work, &personal]).unwrap();
+        assert_eq!(monitor.entry_count(), 2);
+
+        let mut monitor =
+            UsageMonitor::new().with_archive(UsageArchive::open(&archive_path).unwrap());
+        monitor.load_directory(&personal).unwrap();
+        assert_eq!(monitor.entry_count(), 1);
+        assert_eq!(monitor.get_usage_entries()[0].message_id(), Some("msg_2"));
+
+        monitor.load_data(work.join("a.jsonl")).unwrap();
+        assert_eq!(monitor.entry_count(), 1);
+        assert_eq!(monitor.get_usage_entries()[0].message_id(), Some("msg_1"));
+
+        // Archived history of the scoped root still shows after cleanup
+        std::fs::remove_file(personal.join("b.jsonl")).unwrap();
+        let mut monitor =
+            UsageMonitor::new().with_archive(UsageArchive::open(&archive_path).unwrap());
+        monitor.refresh(&personal).unwrap();
+        assert_eq!(monitor.entry_count(), 1);
+        assert_eq!(monitor.get_usage_entries()[0].message_id(), Some("msg_2"));
+    }
+
+    #[test]
+    fn test_limit_model_drives_percentage() {
+        let timestamp = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let entry = UsageEntry::new(
+            timestamp,
+            "claude-opus-4-20250514".to_string(),
+            4000,
+            400,
+            0,
+            0,
+            0.1,
+        );
+
+        let mut weighted = UsageMonitor::new();
+        weighted.add_entry(entry.clone());
+        assert_eq!(weighted.get_current_block_tokens(), 22_000.0);
+        assert_eq!(
+            weighted.get_current_block_percentage(&ClaudePlan::Pro),
+            50.0
+        );
+
+        let mut raw = UsageMonitor::new().with_limit_model(Box::new(crate::limits::RawTokens));
+        raw.add_entry(entry);
+        assert_eq!(raw.limit_model().name(), "raw");
+        assert_eq!(raw.get_current_block_tokens(), 4_400.0);
+        assert_eq!(raw.get_current_block_percentage(&ClaudePlan::Pro), 10.0);
+    }
+
+    #[test]
+    fn test_auto_plan_uses_peak_block() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let entry = |hours: i64, input: u64| {
+            UsageEntry::new(
+                start + Duration::hours(hours),
+                "claude-3-sonnet-20240229".to_string(),
+                input,
+                0,
+                0,
+                0,
+                0.001,
+            )
+        };
+
+        let mut monitor = UsageMonitor::new().with_limit_model(Box::new(crate::limits::RawTokens));
+        assert!(monitor.estimate_token_limit().is_none());
+        assert_eq!(monitor.get_token_limit(&ClaudePlan::Auto), 44_000.0);
+
+        monitor.add_entry(entry(0, 8_000));
+        monitor.add_entry(entry(10, 2_000));
+        let estimate = monitor.estimate_token_limit().unwrap();
+        assert_eq!(estimate.limit(), 8_000.0);
+        assert_eq!(
+            estimate.source(),
+            crate::data_structures::LimitSource::PeakBlock
+        );
+        assert_eq!(
+            monitor.get_current_block_percentage(&ClaudePlan::Auto),
+            25.0
+        );
+        assert_eq!(monitor.get_token_limit(&ClaudePlan::Max5), 220_000.0);
+
+        assert_eq!("Max5".parse::<ClaudePlan>().unwrap(), ClaudePlan::Max5);
+        assert_eq!("auto".parse::<ClaudePlan>().unwrap(), ClaudePlan::Auto);
+        assert!("team".parse::<ClaudePlan>().is_err());
+        assert!("custom".parse::<ClaudePlan>().is_err());
+    }
+
+    #[test]
+    fn test_auto_plan_ignores_block_in_progress() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 2, 12, 0, 0).unwrap();
+        let entry = |hours_ago: i64, input: u64| {
+            UsageEntry::new(
+                now - Duration::hours(hours_ago),
+                "claude-3-sonnet-20240229".to_string(),
+                input,
+                0,
+                0,
+                0,
+                0.001,
+            )
+        };
+
+        let mut monitor = UsageMonitor::new().with_limit_model(Box::new(crate::limits::RawTokens));
+        monitor.add_entry(entry(20, 6_000));
+        monitor.add_entry(entry(10, 8_000));
+        monitor.add_entry(entry(1, 30_000));
+
+        let estimate = monitor.estimate_token_limit_at(now).unwrap();
+        assert_eq!(estimate.limit(), 8_000.0);
+        assert_eq!(
+            estimate.source(),
+            crate::data_structures::LimitSource::PeakBlock
+        );
+
+        // Once the block has ended it counts like any other
+        let later = now + Duration::hours(5);
+        assert_eq!(
+            monitor.estimate_token_limit_at(later).unwrap().limit(),
+            30_000.0
+        );
+    }
+
+    #[test]
+    fn test_custom_plan_limits() {
+        let timestamp = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let mut monitor = UsageMonitor::new().with_limit_model(Box::new(crate::limits::RawTokens));
+        monitor.add_entry(UsageEntry::new(
+            timestamp,
+            "claude-3-sonnet-20240229".to_string(),
+            1000,
+            0,
+            0,
+            0,
+            0.001,
+        ));
+
+        let cost = monitor.get_current_block_cost();
+        let plan = ClaudePlan::Custom(
+            crate::plans::CustomPlan::new("Team", 4000).with_cost_cap(Usd::from_f64(cost * 4.0)),
+        );
+        assert_eq!(monitor.get_current_block_percentage(&plan), 25.0);
+        assert_eq!(monitor.get_current_block_cost_percentage(&plan), Some(25.0));
+        assert_eq!(
+            monitor.get_current_block_cost_percentage(&ClaudePlan::Pro),
+            None
+        );
+    }
+
     #[test]
     fn test_get_current_burn_rate() {
         let mut monitor = UsageMonitor::new();
@@ -421,6 +1068,82 @@ mod tests {
         assert!(breakdown.contains_key("claude-3-opus-20240229"));
     }
 
+    #[test]
+    fn test_get_project_breakdown() {
+        let mut monitor = UsageMonitor::new();
+        let timestamp = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        let entry = |offset: i64, model: &str, project: Option<&str>, cwd: Option<&str>| {
+            UsageEntry::new(
+                timestamp + Duration::minutes(offset),
+                model.to_string(),
+                100,
+                50,
+                0,
+                0,
+                0.001,
+            )
+            .with_project(project.map(str::to_string))
+            .with_cwd(cwd.map(str::to_string))
+        };
+
+        monitor.add_entry(entry(0, "claude-3-sonnet-20240229", Some("client-a"), None));
+        monitor.add_entry(entry(10, "claude-3-opus-20240229", Some("client-a"), None));
+        monitor.add_entry(entry(20, "claude-3-sonnet-20240229", None, Some("/work/b")));
+
+        let breakdown = monitor.get_project_breakdown();
+        assert_eq!(breakdown.len(), 2);
+
+        let client_a = &breakdown["client-a"];
+        assert_eq!(client_a.request_count(), 2);
+        assert_eq!(client_a.token_counts().total_tokens(), 300);
+        assert_eq!(client_a.weighted_tokens(), 150.0 + 150.0 * 5.0);
+        assert_eq!(client_a.first_activity(), timestamp);
+        assert_eq!(client_a.last_activity(), timestamp + Duration::minutes(10));
+        assert_eq!(breakdown["/work/b"].request_count(), 1);
+
+        assert_eq!(monitor.get_current_block_project_breakdown().len(), 2);
+    }
+
+    #[test]
+    fn test_block_and_project_rates_follow_limit_model() {
+        let timestamp = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let entry = |offset: i64| {
+            UsageEntry::new(
+                timestamp + Duration::minutes(offset),
+                "claude-3-opus-20240229".to_string(),
+                100,
+                50,
+                0,
+                0,
+                0.001,
+            )
+            .with_project(Some("client-a".to_string()))
+        };
+
+        let mut monitor = UsageMonitor::new().with_limit_model(Box::new(crate::limits::RawTokens));
+        monitor.add_entry(entry(0));
+        monitor.add_entry(entry(10));
+
+        let block = &monitor.get_session_blocks()[0];
+        let expected = monitor.block_units(block) / block.duration_minutes();
+        let burn_rate = monitor.get_burn_rate_for_block(0).unwrap();
+        assert_eq!(burn_rate.tokens_per_minute(), expected);
+        assert_eq!(
+            monitor.get_peak_burn_rate().unwrap().tokens_per_minute(),
+            expected
+        );
+        assert_eq!(
+            monitor.get_average_burn_rate().unwrap().tokens_per_minute(),
+            expected
+        );
+
+        assert_eq!(
+            monitor.get_project_breakdown()["client-a"].weighted_tokens(),
+            300.0
+        );
+    }
+
     #[test]
     fn test_clear_data() {
         let mut monitor = UsageMonitor::new();
@@ -443,4 +1166,98 @@ mod tests {
         assert_eq!(monitor.session_count(), 0);
         assert_eq!(monitor.entry_count(), 0);
     }
+
+    #[test]
+    fn test_total_cost_in_currency_uses_daily_rates() {
+        let mut monitor = UsageMonitor::new();
+        let first = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let second = Utc.with_ymd_and_hms(2025, 1, 2, 12, 0, 0).unwrap();
+        for (timestamp, cost) in [(first, 1.0), (second, 2.0)] {
+            monitor.add_entry(UsageEntry::new(
+                timestamp,
+                "claude-3-sonnet-20240229".to_string(),
+                100,
+                50,
+                0,
+                0,
+                cost,
+            ));
+        }
+
+        let eur = Currency::new("EUR", "€")
+            .with_rate(first.date_naive(), 0.9)
+            .with_rate(second.date_naive(), 0.8);
+
+        let total = monitor.get_total_cost_in(&eur).unwrap();
+        assert!((total - 2.5).abs() < 1e-9);
+        let anthropic = monitor.get_profile_cost_in(DEFAULT_PROFILE, &eur).unwrap();
+        assert!((anthropic - 2.5).abs() < 1e-9);
+        assert_eq!(monitor.get_profile_cost_in("bedrock", &eur), Some(0.0));
+        assert_eq!(monitor.get_total_cost_in(&Currency::usd()), Some(3.0));
+        assert_eq!(
+            monitor.get_total_cost_in(&Currency::new("CHF", "CHF ")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_weekly_usage_projects_exhaustion() {
+        // Monday 2025-01-06; the default window starts Monday 00:00 UTC
+        let monday = Utc.with_ymd_and_hms(2025, 1, 6, 0, 0, 0).unwrap();
+        let mut monitor = UsageMonitor::new()
+            .with_limit_model(Box::new(crate::limits::RawTokens))
+            .with_weekly_config(
+                WeeklyConfig::new()
+                    .with_all_models_budget(10_000.0)
+                    .with_opus_budget(1_000.0),
+            );
+
+        let last_week = UsageEntry::new(
+            monday - Duration::hours(1),
+            "claude-3-opus-20240229".to_string(),
+            5_000,
+            0,
+            0,
+            0,
+            0.0,
+        );
+        monitor.add_entry(last_week);
+        for (hours, model) in [
+            (1, "claude-3-sonnet-20240229"),
+            (2, "claude-3-opus-20240229"),
+        ] {
+            monitor.add_entry(UsageEntry::new(
+                monday + Duration::hours(hours),
+                model.to_string(),
+                500,
+                0,
+                0,
+                0,
+                0.0,
+            ));
+        }
+
+        let now = monday + Duration::hours(4);
+        let weekly = monitor.get_weekly_usage_at(now);
+        assert_eq!(weekly.window_start(), monday);
+        assert_eq!(weekly.resets_at(), monday + Duration::weeks(1));
+        assert_eq!(weekly.all_models().used(), 1_000.0);
+        assert_eq!(weekly.all_models().percentage(), Some(10.0));
+        assert_eq!(weekly.opus().used(), 500.0);
+
+        // 500 Opus units in 4 hours reach 1,000 after another 4 hours
+        assert_eq!(
+            weekly.opus().projected_exhaustion(),
+            Some(now + Duration::hours(4))
+        );
+        assert_eq!(
+            weekly.projected_exhaustion(),
+            Some(now + Duration::hours(4))
+        );
+        // 1,000 units in 4 hours reach 10,000 after 36 more, within the week
+        assert_eq!(
+            weekly.all_models().projected_exhaustion(),
+            Some(now + Duration::hours(36))
+        );
+    }
 }
@@ -0,0 +1,225 @@
+use crate::config::find_config_file;
+use crate::cost::Usd;
+use crate::data_structures::ClaudePlan;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A plan defined in config, for seats and budgets the built-in plans
+/// don't describe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomPlan {
+    name: String,
+    token_limit: u64,
+    weekly_limit: Option<f64>,
+    cost_cap: Option<Usd>,
+}
+
+impl CustomPlan {
+    pub fn new(name: &str, token_limit: u64) -> Self {
+        Self {
+            name: name.to_string(),
+            token_limit,
+            weekly_limit: None,
+            cost_cap: None,
+        }
+    }
+
+    pub fn with_weekly_limit(mut self, limit: f64) -> Self {
+        self.weekly_limit = Some(limit);
+        self
+    }
+
+    pub fn with_cost_cap(mut self, cap: Usd) -> Self {
+        self.cost_cap = Some(cap);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Per-block limit, in limit-model units.
+    pub fn token_limit(&self) -> u64 {
+        self.token_limit
+    }
+
+    /// Weekly all-models budget, in limit-model units.
+    pub fn weekly_limit(&self) -> Option<f64> {
+        self.weekly_limit
+    }
+
+    /// Spending cap per block.
+    pub fn cost_cap(&self) -> Option<Usd> {
+        self.cost_cap
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PlansFile {
+    #[serde(default)]
+    plans: HashMap<String, PlanEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlanEntry {
+    name: Option<String>,
+    token_limit: u64,
+    weekly_limit: Option<f64>,
+    /// USD per block.
+    cost_cap: Option<f64>,
+}
+
+/// Custom plans read from a local file, selectable by their key.
+///
+/// ```toml
+/// [plans.team]
+/// name = "Claude Team"
+/// token_limit = 150000
+/// weekly_limit = 2000000
+/// cost_cap = 40.0
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PlanRegistry {
+    plans: HashMap<String, CustomPlan>,
+}
+
+impl PlanRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read plans: {}", path.display()))?;
+
+        let file: PlansFile = if path.extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str(&content)
+                .with_context(|| format!("Invalid plans: {}", path.display()))?
+        } else {
+            serde_json::from_str(&content)
+                .with_context(|| format!("Invalid plans: {}", path.display()))?
+        };
+
+        let mut registry = Self::new();
+        for (key, entry) in file.plans {
+            if entry.token_limit == 0 {
+                return Err(anyhow::anyhow!("Plan '{}' has a zero token limit", key));
+            }
+            if let Some(limit) = entry.weekly_limit.filter(|l| !(l.is_finite() && *l > 0.0)) {
+                return Err(anyhow::anyhow!(
+                    "Invalid weekly limit for plan '{}': {}",
+                    key,
+                    limit
+                ));
+            }
+            if let Some(cap) = entry.cost_cap.filter(|c| !(c.is_finite() && *c > 0.0)) {
+                return Err(anyhow::anyhow!(
+                    "Invalid cost cap for plan '{}': {}",
+                    key,
+                    cap
+                ));
+            }
+
+            let mut plan =
+                CustomPlan::new(entry.name.as_deref().unwrap_or(&key), entry.token_limit);
+            if let Some(limit) = entry.weekly_limit {
+                plan = plan.with_weekly_limit(limit);
+            }
+            if let Some(cap) = entry.cost_cap {
+                plan = plan.with_cost_cap(Usd::from_f64(cap));
+            }
+            registry.add_plan(&key, plan);
+        }
+
+        Ok(registry)
+    }
+
+    /// `plans.toml` or `plans.json` in [`crate::config_dir`], whichever
+    /// exists.
+    pub fn default_config_path() -> Option<PathBuf> {
+        find_config_file(&["plans.toml", "plans.json"])
+    }
+
+    pub fn add_plan(&mut self, key: &str, plan: CustomPlan) {
+        self.plans.insert(key.to_lowercase(), plan);
+    }
+
+    pub fn get(&self, key: &str) -> Option<&CustomPlan> {
+        self.plans.get(&key.to_lowercase())
+    }
+
+    /// Looks up `name`, case-insensitively, among the custom plans and then
+    /// the built-in ones, so a custom plan may redefine e.g. `pro`. A bare
+    /// `custom` is an error unless a plan is named that.
+    pub fn plan(&self, name: &str) -> Result<ClaudePlan> {
+        match self.get(name) {
+            Some(plan) => Ok(ClaudePlan::Custom(plan.clone())),
+            None if name.eq_ignore_ascii_case("custom") => name.parse(),
+            None => name.parse().map_err(|_| {
+                let mut known = vec!["pro", "max5", "max20", "auto"];
+                known.extend(self.keys());
+                anyhow::anyhow!("Unknown plan '{}' (expected {})", name, known.join(", "))
+            }),
+        }
+    }
+
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.plans.keys().map(String::as_str).collect();
+        keys.sort();
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_plans_from_toml() {
+        let mut file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+        writeln!(
+            file,
+            r#"
+[plans.Team]
+name = "Claude Team"
+token_limit = 150000
+weekly_limit = 2000000
+cost_cap = 40.0
+
+[plans.api]
+token_limit = 500000
+"#
+        )
+        .unwrap();
+
+        let registry = PlanRegistry::from_file(file.path()).unwrap();
+        assert_eq!(registry.keys(), vec!["api", "team"]);
+
+        let team = registry.plan("TEAM").unwrap();
+        assert_eq!(team.name(), "Claude Team");
+        assert_eq!(team.max_tokens(), 150_000);
+        assert_eq!(team.weekly_limit(), Some(2_000_000.0));
+        assert_eq!(team.cost_cap(), Some(Usd::from_f64(40.0)));
+
+        let api = registry.plan("api").unwrap();
+        assert_eq!(api.name(), "api");
+        assert_eq!(api.cost_cap(), None);
+
+        assert_eq!(registry.plan("max5").unwrap(), ClaudePlan::Max5);
+        let err = registry.plan("enterprise").unwrap_err().to_string();
+        assert!(err.contains("api, team"), "{}", err);
+        let err = registry.plan("custom").unwrap_err().to_string();
+        assert!(err.contains("--plan <name>"), "{}", err);
+    }
+
+    #[test]
+    fn test_plans_reject_invalid_limits() {
+        let mut file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+        writeln!(file, "[plans.broken]\ntoken_limit = 1000\ncost_cap = -5.0").unwrap();
+        assert!(PlanRegistry::from_file(file.path()).is_err());
+    }
+}
@@ -1,8 +1,191 @@
-use crate::data_structures::ModelPricing;
-use std::collections::HashMap;
+use crate::config::find_config_file;
+use crate::cost::Usd;
+use crate::data_structures::{ModelPricing, UsageEntry};
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
+const PER_MILLION: f64 = 1_000_000.0;
+/// Profile holding the Anthropic API list prices, which other profiles
+/// override model by model.
+pub const DEFAULT_PROFILE: &str = "anthropic";
+/// Prompt size above which Sonnet 4 charges long-context rates.
+const LONG_CONTEXT_THRESHOLD: u64 = 200_000;
+
+#[derive(Debug, Clone)]
 pub struct PricingProvider {
-    pricing_cache: HashMap<String, ModelPricing>,
+    /// Pricing periods per model; see [`ModelPricing::with_effective_period`].
+    pricing_cache: HashMap<String, Vec<ModelPricing>>,
+    model_weights: HashMap<String, f64>,
+    /// Per-profile overrides of `pricing_cache`, e.g. Bedrock regional rates.
+    profiles: HashMap<String, HashMap<String, Vec<ModelPricing>>>,
+    /// Profile used for entries without one of their own.
+    default_profile: Option<String>,
+}
+
+/// How [`PricingProvider::resolve`] matched a model id to a pricing entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MatchRule {
+    /// The id is in the pricing table as-is.
+    Exact,
+    /// Matched after removing Bedrock/Vertex prefixes and version suffixes.
+    Normalized,
+    /// Matched the same model with a different (or no) date stamp.
+    Undated,
+    /// Fell back to the closest model of the same family and major
+    /// generation.
+    Family,
+}
+
+impl MatchRule {
+    pub fn description(&self) -> &'static str {
+        match self {
+            MatchRule::Exact => "exact",
+            MatchRule::Normalized => "provider id",
+            MatchRule::Undated => "undated alias",
+            MatchRule::Family => "family fallback",
+        }
+    }
+}
+
+/// Pricing entry a model id resolved to.
+#[derive(Debug, Clone, Copy)]
+pub struct ResolvedModel<'a> {
+    model: &'a str,
+    pricing: &'a ModelPricing,
+    rule: MatchRule,
+}
+
+impl<'a> ResolvedModel<'a> {
+    /// Id of the pricing entry that matched.
+    pub fn model(&self) -> &'a str {
+        self.model
+    }
+
+    pub fn pricing(&self) -> &'a ModelPricing {
+        self.pricing
+    }
+
+    pub fn rule(&self) -> MatchRule {
+        self.rule
+    }
+}
+
+/// User pricing file. Rates are in dollars per million tokens, e.g.
+///
+/// ```toml
+/// [models."claude-opus-4-1-20250805"]
+/// input = 15.0
+/// output = 75.0
+/// cache_write = 18.75
+/// cache_write_1h = 30.0
+/// cache_read = 1.5
+/// weight = 5.0
+///
+/// # Older rates, in force until the given date
+/// [models."claude-3-5-haiku-20241022"]
+/// effective_until = "2025-01-01"
+/// input = 0.8
+/// output = 4.0
+///
+/// # Rates that apply only to entries priced with the `bedrock` profile
+/// [profiles.bedrock.models."claude-sonnet-4-20250514"]
+/// input = 3.3
+/// output = 16.5
+///
+/// # Rates for requests whose prompt is larger than `above` tokens
+/// [[models."claude-opus-4-1-20250805".tiers]]
+/// above = 200000
+/// input = 30.0
+/// output = 112.5
+/// ```
+#[derive(Debug, Default, Deserialize)]
+struct PricingFile {
+    #[serde(default)]
+    models: HashMap<String, ModelRates>,
+    #[serde(default)]
+    profiles: HashMap<String, ProfileRates>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ProfileRates {
+    #[serde(default)]
+    models: HashMap<String, ModelRates>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ModelRates {
+    input: Option<f64>,
+    output: Option<f64>,
+    cache_write: Option<f64>,
+    cache_write_1h: Option<f64>,
+    cache_read: Option<f64>,
+    weight: Option<f64>,
+    tiers: Option<Vec<TierRates>>,
+    effective_from: Option<String>,
+    effective_until: Option<String>,
+}
+
+/// One entry of a LiteLLM `model_prices_and_context_window.json` catalog.
+/// Rates are per token.
+#[derive(Debug, Deserialize)]
+struct LiteLlmModel {
+    litellm_provider: Option<String>,
+    input_cost_per_token: Option<f64>,
+    output_cost_per_token: Option<f64>,
+    cache_creation_input_token_cost: Option<f64>,
+    cache_creation_input_token_cost_above_1hr: Option<f64>,
+    cache_read_input_token_cost: Option<f64>,
+    input_cost_per_token_above_200k_tokens: Option<f64>,
+    output_cost_per_token_above_200k_tokens: Option<f64>,
+    cache_creation_input_token_cost_above_200k_tokens: Option<f64>,
+    cache_read_input_token_cost_above_200k_tokens: Option<f64>,
+}
+
+impl LiteLlmModel {
+    fn pricing(&self) -> Option<ModelPricing> {
+        let input = self.input_cost_per_token?;
+        let output = self.output_cost_per_token?;
+        let pricing = ModelPricing::new(
+            input,
+            output,
+            self.cache_creation_input_token_cost.unwrap_or(input * 1.25),
+            self.cache_read_input_token_cost.unwrap_or(input * 0.1),
+        )
+        .with_cache_creation_1h_cost(
+            self.cache_creation_input_token_cost_above_1hr
+                .unwrap_or(input * 2.0),
+        );
+
+        let (Some(long_input), Some(long_output)) = (
+            self.input_cost_per_token_above_200k_tokens,
+            self.output_cost_per_token_above_200k_tokens,
+        ) else {
+            return Some(pricing);
+        };
+        let long_context = ModelPricing::new(
+            long_input,
+            long_output,
+            self.cache_creation_input_token_cost_above_200k_tokens
+                .unwrap_or(long_input * 1.25),
+            self.cache_read_input_token_cost_above_200k_tokens
+                .unwrap_or(long_input * 0.1),
+        )
+        .with_cache_creation_1h_cost(long_input * 2.0);
+        Some(pricing.with_tier(LONG_CONTEXT_THRESHOLD, long_context))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TierRates {
+    above: u64,
+    input: f64,
+    output: f64,
+    cache_write: Option<f64>,
+    cache_write_1h: Option<f64>,
+    cache_read: Option<f64>,
 }
 
 impl PricingProvider {
@@ -87,14 +270,461 @@ impl PricingProvider {
                 15.0 / 1_000_000.0, // $15 per 1M output tokens
                 3.75 / 1_000_000.0, // $3.75 per 1M cache creation tokens
                 0.3 / 1_000_000.0,  // $0.3 per 1M cache read tokens
+            )
+            // Long-context rates for prompts over 200K tokens (1M context window)
+            .with_tier(
+                LONG_CONTEXT_THRESHOLD,
+                ModelPricing::new(
+                    6.0 / 1_000_000.0,  // $6 per 1M input tokens
+                    22.5 / 1_000_000.0, // $22.50 per 1M output tokens
+                    7.5 / 1_000_000.0,  // $7.50 per 1M cache creation tokens
+                    0.6 / 1_000_000.0,  // $0.6 per 1M cache read tokens
+                ),
             ),
         );
 
-        Self { pricing_cache }
+        let pricing_cache = pricing_cache
+            .into_iter()
+            .map(|(model, pricing)| (model, vec![pricing]))
+            .collect();
+
+        let mut model_weights = HashMap::new();
+        for model in ["claude-3-opus-20240229", "claude-opus-4-20250514"] {
+            model_weights.insert(model.to_string(), 5.0);
+        }
+        for model in ["claude-3-haiku-20240307", "claude-3-5-haiku-20241022"] {
+            model_weights.insert(model.to_string(), 0.2);
+        }
+
+        // Bedrock and Vertex start at the Anthropic list prices; regional
+        // rates are added through the pricing file
+        let profiles = ["bedrock", "vertex"]
+            .into_iter()
+            .map(|profile| (profile.to_string(), HashMap::new()))
+            .collect();
+
+        Self {
+            pricing_cache,
+            model_weights,
+            profiles,
+            default_profile: None,
+        }
+    }
+
+    /// Built-in pricing merged with the rates from `path`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut provider = Self::new();
+        provider.merge_file(path)?;
+        Ok(provider)
+    }
+
+    /// Built-in pricing, then the default price catalog and pricing file,
+    /// if they exist.
+    pub fn load_default() -> Result<Self> {
+        Self::load(None, None)
+    }
+
+    /// Built-in pricing overlaid with a LiteLLM price catalog and then a
+    /// pricing file. Each path falls back to its default location.
+    pub fn load(catalog: Option<&Path>, pricing: Option<&Path>) -> Result<Self> {
+        let mut provider = Self::new();
+        if let Some(path) = catalog
+            .map(Path::to_path_buf)
+            .or_else(Self::default_catalog_path)
+        {
+            provider.import_litellm(path)?;
+        }
+        if let Some(path) = pricing
+            .map(Path::to_path_buf)
+            .or_else(Self::default_config_path)
+        {
+            provider.merge_file(path)?;
+        }
+        Ok(provider)
+    }
+
+    /// `model_prices_and_context_window.json` in [`crate::config_dir`], if
+    /// it exists.
+    pub fn default_catalog_path() -> Option<PathBuf> {
+        find_config_file(&["model_prices_and_context_window.json"])
+    }
+
+    /// `pricing.toml` or `pricing.json` in [`crate::config_dir`], whichever
+    /// exists.
+    pub fn default_config_path() -> Option<PathBuf> {
+        find_config_file(&["pricing.toml", "pricing.json"])
+    }
+
+    /// Merges a TOML or JSON pricing file (chosen by extension) over the
+    /// current table. Fields left out keep their current value; new models
+    /// need at least `input` and `output`.
+    pub fn merge_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read pricing file: {}", path.display()))?;
+
+        let file: PricingFile = if path.extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str(&content)
+                .with_context(|| format!("Invalid pricing file: {}", path.display()))?
+        } else {
+            serde_json::from_str(&content)
+                .with_context(|| format!("Invalid pricing file: {}", path.display()))?
+        };
+
+        for (model, rates) in file.models {
+            self.merge_rates(None, model, rates)?;
+        }
+        for (profile, profile_rates) in file.profiles {
+            self.add_profile(&profile);
+            for (model, rates) in profile_rates.models {
+                self.merge_rates(Some(&profile), model, rates)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn merge_rates(
+        &mut self,
+        profile: Option<&str>,
+        model: String,
+        rates: ModelRates,
+    ) -> Result<()> {
+        let effective_from = rates
+            .effective_from
+            .as_deref()
+            .map(parse_effective_date)
+            .transpose()?;
+        let effective_until = rates
+            .effective_until
+            .as_deref()
+            .map(parse_effective_date)
+            .transpose()?;
+
+        // Profile rates left out are inherited from the base table
+        let periods = profile
+            .and_then(|profile| self.profiles.get(profile))
+            .and_then(|table| table.get(&model))
+            .or_else(|| self.pricing_cache.get(&model));
+        let same_period = periods.and_then(|periods| {
+            periods.iter().position(|pricing| {
+                pricing.effective_from() == effective_from
+                    && pricing.effective_until() == effective_until
+            })
+        });
+        // Rates left out of a new period are inherited from the current one
+        let base = match (periods, same_period) {
+            (Some(periods), Some(index)) => Some(&periods[index]),
+            (Some(periods), None) => Some(period_at(periods, Utc::now())),
+            (None, _) => None,
+        };
+
+        let pricing = match base {
+            Some(current) => ModelPricing::new(
+                rates
+                    .input
+                    .map_or(current.input_cost_per_token(), |rate| rate / PER_MILLION),
+                rates
+                    .output
+                    .map_or(current.output_cost_per_token(), |rate| rate / PER_MILLION),
+                rates
+                    .cache_write
+                    .map_or(current.cache_creation_input_token_cost(), |rate| {
+                        rate / PER_MILLION
+                    }),
+                rates
+                    .cache_read
+                    .map_or(current.cache_read_input_token_cost(), |rate| {
+                        rate / PER_MILLION
+                    }),
+            )
+            .with_cache_creation_1h_cost(
+                rates
+                    .cache_write_1h
+                    .map_or(current.cache_creation_1h_input_token_cost(), |rate| {
+                        rate / PER_MILLION
+                    }),
+            )
+            .with_tiers(current.tiers().to_vec()),
+            None => {
+                let (Some(input), Some(output)) = (rates.input, rates.output) else {
+                    anyhow::bail!(
+                        "Pricing for new model {} needs input and output rates",
+                        model
+                    );
+                };
+                per_million_pricing(
+                    input,
+                    output,
+                    rates.cache_write,
+                    rates.cache_write_1h,
+                    rates.cache_read,
+                )
+            }
+        };
+
+        let pricing = match rates.tiers {
+            Some(tiers) => pricing.with_tiers(
+                tiers
+                    .into_iter()
+                    .map(|tier| {
+                        let tier_pricing = per_million_pricing(
+                            tier.input,
+                            tier.output,
+                            tier.cache_write,
+                            tier.cache_write_1h,
+                            tier.cache_read,
+                        );
+                        (tier.above, tier_pricing)
+                    })
+                    .collect(),
+            ),
+            None => pricing,
+        };
+
+        if let Some(weight) = rates.weight {
+            self.model_weights.insert(model.clone(), weight);
+        }
+        let pricing = pricing.with_effective_period(effective_from, effective_until);
+        match profile {
+            Some(profile) => self.add_profile_pricing(profile, &model, pricing),
+            None => self.add_pricing(&model, pricing),
+        }
+        Ok(())
+    }
+
+    /// Imports the Claude models from a local LiteLLM
+    /// `model_prices_and_context_window.json`, replacing their current
+    /// (undated) pricing. Direct Anthropic entries become the list prices;
+    /// Bedrock and Vertex entries fill the `bedrock` and `vertex` profiles,
+    /// preferring global over regional ids. Other resellers are skipped.
+    /// Returns the number of prices imported.
+    pub fn import_litellm<P: AsRef<Path>>(&mut self, path: P) -> Result<usize> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read price catalog: {}", path.display()))?;
+        // Entries are parsed one by one so that the catalog's `sample_spec`
+        // and models with unexpected fields don't fail the whole import
+        let catalog: BTreeMap<String, serde_json::Value> = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid price catalog: {}", path.display()))?;
+
+        // Keyed by (profile, model); a lower rank wins, ties keep the first
+        // id in catalog order
+        let mut imported = HashMap::new();
+        for (id, value) in catalog {
+            let Ok(entry) = serde_json::from_value::<LiteLlmModel>(value) else {
+                continue;
+            };
+            let Some(profile) = entry.litellm_provider.as_deref().and_then(litellm_profile) else {
+                continue;
+            };
+            let model = normalize_model_id(&id);
+            if !model.starts_with("claude") {
+                continue;
+            }
+            let Some(pricing) = entry.pricing() else {
+                continue;
+            };
+
+            // Global ids beat regional ones, and an entry listed under the
+            // model's own id beats aliases of it
+            let rank = (is_regional_id(&id), id != model);
+            match imported.get(&(profile, model.clone())) {
+                Some((best, _)) if *best <= rank => {}
+                _ => {
+                    imported.insert((profile, model), (rank, pricing));
+                }
+            }
+        }
+
+        let count = imported.len();
+        for ((profile, model), (_, pricing)) in imported {
+            self.add_profile_pricing(profile, &model, pricing);
+        }
+        Ok(count)
+    }
+
+    /// Replaces every pricing period of `model` with `pricing`.
+    pub fn set_pricing(&mut self, model: &str, pricing: ModelPricing) {
+        self.pricing_cache.insert(model.to_string(), vec![pricing]);
+    }
+
+    /// Adds a pricing period for `model`, replacing one with the same
+    /// effective dates.
+    pub fn add_pricing(&mut self, model: &str, pricing: ModelPricing) {
+        let periods = self.pricing_cache.entry(model.to_string()).or_default();
+        periods.retain(|period| {
+            period.effective_from() != pricing.effective_from()
+                || period.effective_until() != pricing.effective_until()
+        });
+        periods.push(pricing);
+    }
+
+    /// Adds a pricing period for `model` that applies only to entries priced
+    /// with `profile`, creating the profile if needed.
+    pub fn add_profile_pricing(&mut self, profile: &str, model: &str, pricing: ModelPricing) {
+        if profile == DEFAULT_PROFILE {
+            return self.add_pricing(model, pricing);
+        }
+        let periods = self
+            .profiles
+            .entry(profile.to_string())
+            .or_default()
+            .entry(model.to_string())
+            .or_default();
+        periods.retain(|period| {
+            period.effective_from() != pricing.effective_from()
+                || period.effective_until() != pricing.effective_until()
+        });
+        periods.push(pricing);
+    }
+
+    /// Registers an empty profile, which prices like the default one until
+    /// rates are added to it.
+    pub fn add_profile(&mut self, profile: &str) {
+        if profile != DEFAULT_PROFILE {
+            self.profiles.entry(profile.to_string()).or_default();
+        }
+    }
+
+    pub fn has_profile(&self, profile: &str) -> bool {
+        profile == DEFAULT_PROFILE || self.profiles.contains_key(profile)
+    }
+
+    pub fn profile_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+        names.push(DEFAULT_PROFILE);
+        names.sort();
+        names
+    }
+
+    /// Prices every entry without a profile of its own with `profile`.
+    pub fn set_default_profile(&mut self, profile: &str) -> Result<()> {
+        if !self.has_profile(profile) {
+            anyhow::bail!(
+                "Unknown pricing profile '{}' (expected one of: {})",
+                profile,
+                self.profile_names().join(", ")
+            );
+        }
+        self.default_profile = Some(profile.to_string());
+        Ok(())
+    }
+
+    pub fn default_profile(&self) -> Option<&str> {
+        self.default_profile.as_deref()
+    }
+
+    /// Profile to price a `model` entry with: the data root's profile if it
+    /// has one, then the default profile, then one guessed from Bedrock or
+    /// Vertex style model ids.
+    pub fn profile_for(&self, model: &str, root_profile: Option<&str>) -> String {
+        if let Some(profile) = root_profile.or(self.default_profile.as_deref()) {
+            return profile.to_string();
+        }
+
+        let model = model.to_ascii_lowercase();
+        let profile = if model.contains("anthropic.") {
+            "bedrock"
+        } else if model.contains('@') || model.starts_with("vertex_ai/") {
+            "vertex"
+        } else {
+            DEFAULT_PROFILE
+        };
+        profile.to_string()
+    }
+
+    pub fn set_model_weight(&mut self, model: &str, weight: f64) {
+        self.model_weights.insert(model.to_string(), weight);
     }
 
+    /// Current pricing for `model`.
     pub fn get_pricing(&self, model: &str) -> Option<&ModelPricing> {
-        self.pricing_cache.get(model)
+        self.resolve(model).map(|resolved| resolved.pricing())
+    }
+
+    /// Pricing for `model` that was in force at `timestamp`.
+    pub fn get_pricing_at(&self, model: &str, timestamp: DateTime<Utc>) -> Option<&ModelPricing> {
+        self.resolve_at(model, timestamp)
+            .map(|resolved| resolved.pricing())
+    }
+
+    pub fn resolve(&self, model: &str) -> Option<ResolvedModel<'_>> {
+        self.resolve_at(model, Utc::now())
+    }
+
+    /// Finds the pricing entry for `model`, trying in order: the exact id,
+    /// the id without Bedrock/Vertex decorations, the same model under another
+    /// date stamp, and the closest model of the same family. The pricing is
+    /// the period in force at `timestamp`.
+    pub fn resolve_at(&self, model: &str, timestamp: DateTime<Utc>) -> Option<ResolvedModel<'_>> {
+        let (key, periods, rule) = self.resolve_key(model)?;
+        Some(ResolvedModel {
+            model: key,
+            pricing: period_at(periods, timestamp),
+            rule,
+        })
+    }
+
+    /// Like [`PricingProvider::resolve_at`], but prefers the rates `profile`
+    /// sets for the model.
+    pub fn resolve_in(
+        &self,
+        profile: &str,
+        model: &str,
+        timestamp: DateTime<Utc>,
+    ) -> Option<ResolvedModel<'_>> {
+        let overrides = self.profiles.get(profile).and_then(|table| {
+            if let Some((key, periods)) = table.get_key_value(model) {
+                return Some((key, periods, MatchRule::Exact));
+            }
+            table
+                .get_key_value(&normalize_model_id(model))
+                .map(|(key, periods)| (key, periods, MatchRule::Normalized))
+        });
+
+        match overrides {
+            Some((key, periods, rule)) => Some(ResolvedModel {
+                model: key,
+                pricing: period_at(periods, timestamp),
+                rule,
+            }),
+            None => self.resolve_at(model, timestamp),
+        }
+    }
+
+    fn resolve_key(&self, model: &str) -> Option<(&str, &[ModelPricing], MatchRule)> {
+        if let Some((key, periods)) = self.pricing_cache.get_key_value(model) {
+            return Some((key, periods, MatchRule::Exact));
+        }
+
+        let normalized = normalize_model_id(model);
+        if let Some((key, periods)) = self.pricing_cache.get_key_value(&normalized) {
+            return Some((key, periods, MatchRule::Normalized));
+        }
+
+        let undated = strip_date(&normalized);
+        if let Some((key, periods)) = self
+            .pricing_cache
+            .iter()
+            .filter(|(key, _)| strip_date(key) == undated)
+            .max_by_key(|(key, _)| key.as_str())
+        {
+            return Some((key, periods, MatchRule::Undated));
+        }
+
+        let (family, generation) = model_family(&normalized)?;
+        self.pricing_cache
+            .iter()
+            .filter_map(|(key, periods)| {
+                let (key_family, key_generation) = model_family(key)?;
+                (key_family == family && key_generation.0 == generation.0).then_some((
+                    key,
+                    periods,
+                    key_generation,
+                ))
+            })
+            .max_by_key(|(key, _, key_generation)| (*key_generation, key.as_str()))
+            .map(|(key, periods, _)| (key.as_str(), periods.as_slice(), MatchRule::Family))
     }
 
     pub fn calculate_cost(
@@ -102,29 +732,48 @@ impl PricingProvider {
         model: &str,
         input_tokens: u64,
         output_tokens: u64,
-        cache_creation_tokens: u64,
+        cache_creation_5m_tokens: u64,
+        cache_creation_1h_tokens: u64,
         cache_read_tokens: u64,
-    ) -> Option<f64> {
-        self.pricing_cache.get(model).map(|pricing| {
+    ) -> Option<Usd> {
+        self.get_pricing(model).map(|pricing| {
             pricing.calculate_cost(
                 input_tokens,
                 output_tokens,
-                cache_creation_tokens,
+                cache_creation_5m_tokens,
+                cache_creation_1h_tokens,
                 cache_read_tokens,
             )
         })
     }
 
+    /// Cost of `entry` at the rates in force at its timestamp in its pricing
+    /// profile, using the tier that matches the entry's prompt size.
+    pub fn cost_for_entry(&self, entry: &UsageEntry) -> Option<Usd> {
+        let profile = match entry.pricing_profile() {
+            Some(profile) => profile.to_string(),
+            None => self.profile_for(entry.model(), None),
+        };
+        let pricing = self
+            .resolve_in(&profile, entry.model(), entry.timestamp())?
+            .pricing();
+        Some(pricing.calculate_cost(
+            entry.input_tokens(),
+            entry.output_tokens(),
+            entry.cache_creation_5m_input_tokens(),
+            entry.cache_creation_1h_input_tokens(),
+            entry.cache_read_input_tokens(),
+        ))
+    }
+
     pub fn get_model_weight(&self, model: &str) -> f64 {
-        match model {
-            "claude-3-opus-20240229" | "claude-opus-4-20250514" => 5.0,
-            "claude-3-sonnet-20240229"
-            | "claude-3-5-sonnet-20240620"
-            | "claude-3-5-sonnet-20241022"
-            | "claude-sonnet-4-20250514" => 1.0,
-            "claude-3-haiku-20240307" | "claude-3-5-haiku-20241022" => 0.2,
-            _ => 1.0,
+        if let Some(weight) = self.model_weights.get(model) {
+            return *weight;
         }
+
+        self.resolve(model)
+            .and_then(|resolved| self.model_weights.get(resolved.model()).copied())
+            .unwrap_or(1.0)
     }
 
     pub fn supported_models(&self) -> Vec<&String> {
@@ -137,3 +786,491 @@ impl Default for PricingProvider {
         Self::new()
     }
 }
+
+/// The period of `periods` in force at `timestamp`. When several apply the
+/// one starting last wins; when none does, the closest earlier period (or the
+/// earliest one) is used.
+fn period_at(periods: &[ModelPricing], timestamp: DateTime<Utc>) -> &ModelPricing {
+    let in_force = periods
+        .iter()
+        .enumerate()
+        .filter(|(_, pricing)| pricing.is_effective_at(timestamp))
+        .max_by_key(|(index, pricing)| (pricing.effective_from(), *index))
+        .map(|(_, pricing)| pricing);
+
+    in_force.unwrap_or_else(|| {
+        periods
+            .iter()
+            .filter(|pricing| {
+                pricing
+                    .effective_from()
+                    .is_none_or(|from| from <= timestamp)
+            })
+            .max_by_key(|pricing| pricing.effective_from())
+            .unwrap_or_else(|| {
+                periods
+                    .iter()
+                    .min_by_key(|pricing| pricing.effective_from())
+                    .expect("models have at least one pricing period")
+            })
+    })
+}
+
+/// Parses `YYYY-MM-DD` (midnight UTC) or an RFC 3339 timestamp.
+fn parse_effective_date(value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+
+    DateTime::parse_from_rfc3339(value)
+        .map(|date| date.with_timezone(&Utc))
+        .with_context(|| format!("Invalid effective date: {}", value))
+}
+
+/// Builds pricing from per-million rates, filling in Anthropic's standard
+/// cache multipliers for rates that are left out.
+fn per_million_pricing(
+    input: f64,
+    output: f64,
+    cache_write: Option<f64>,
+    cache_write_1h: Option<f64>,
+    cache_read: Option<f64>,
+) -> ModelPricing {
+    ModelPricing::new(
+        input / PER_MILLION,
+        output / PER_MILLION,
+        cache_write.unwrap_or(input * 1.25) / PER_MILLION,
+        cache_read.unwrap_or(input * 0.1) / PER_MILLION,
+    )
+    .with_cache_creation_1h_cost(cache_write_1h.unwrap_or(input * 2.0) / PER_MILLION)
+}
+
+/// Pricing profile for a LiteLLM `litellm_provider`, or `None` for
+/// providers that are not imported.
+fn litellm_profile(provider: &str) -> Option<&'static str> {
+    match provider {
+        "anthropic" => Some(DEFAULT_PROFILE),
+        provider if provider.starts_with("bedrock") => Some("bedrock"),
+        provider if provider.starts_with("vertex_ai") => Some("vertex"),
+        _ => None,
+    }
+}
+
+/// Whether a catalog id is region-specific, e.g. `us.anthropic.claude-…` or
+/// `bedrock/us-east-1/anthropic.claude-…`.
+fn is_regional_id(id: &str) -> bool {
+    let name = id.rsplit('/').next().unwrap_or(id);
+    id.matches('/').count() > 1 || name.find("anthropic.").is_some_and(|pos| pos > 0)
+}
+
+/// Strips provider decorations from a model id, e.g.
+/// `us.anthropic.claude-3-5-sonnet-20241022-v2:0` (Bedrock) and
+/// `claude-3-5-sonnet-v2@20241022` (Vertex) both become
+/// `claude-3-5-sonnet-20241022`.
+fn normalize_model_id(model: &str) -> String {
+    let mut id = model.trim().to_ascii_lowercase();
+
+    if let Some(pos) = id.rfind('/') {
+        id = id[pos + 1..].to_string();
+    }
+    if let Some(pos) = id.find("anthropic.") {
+        id = id[pos + "anthropic.".len()..].to_string();
+    }
+
+    let (name, date) = match id.split_once('@') {
+        Some((name, date)) => (name, Some(date)),
+        None => (id.as_str(), None),
+    };
+
+    let name = match name.rsplit_once(':') {
+        Some((rest, version)) if is_digits(version) => rest,
+        _ => name,
+    };
+    let name = match name.rsplit_once("-v") {
+        Some((rest, version)) if is_digits(version) => rest,
+        _ => name,
+    };
+
+    match date {
+        Some(date) if is_digits(date) => format!("{}-{}", name, date),
+        _ => name.to_string(),
+    }
+}
+
+/// Removes a trailing `-YYYYMMDD` or `-latest` from a model id.
+fn strip_date(model: &str) -> &str {
+    match model.rsplit_once('-') {
+        Some((rest, suffix)) if suffix == "latest" || (suffix.len() == 8 && is_digits(suffix)) => {
+            rest
+        }
+        _ => model,
+    }
+}
+
+/// Family name and (major, minor) generation, e.g. `("sonnet", (3, 5))` for
+/// both `claude-3-5-sonnet-20241022` and `claude-sonnet-3-5`.
+fn model_family(model: &str) -> Option<(&'static str, (u32, u32))> {
+    let mut family = None;
+    let mut numbers = Vec::new();
+
+    for part in strip_date(model).split('-') {
+        match part {
+            "opus" => family = Some("opus"),
+            "sonnet" => family = Some("sonnet"),
+            "haiku" => family = Some("haiku"),
+            _ if part.len() <= 2 && is_digits(part) => numbers.extend(part.parse::<u32>().ok()),
+            _ => {}
+        }
+    }
+
+    let major = *numbers.first()?;
+    Some((family?, (major, numbers.get(1).copied().unwrap_or(0))))
+}
+
+fn is_digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_resolve_provider_ids() {
+        let provider = PricingProvider::new();
+
+        for id in [
+            "anthropic.claude-3-5-sonnet-20241022-v2:0",
+            "us.anthropic.claude-3-5-sonnet-20241022-v2:0",
+            "claude-3-5-sonnet-v2@20241022",
+        ] {
+            let resolved = provider.resolve(id).unwrap();
+            assert_eq!(resolved.model(), "claude-3-5-sonnet-20241022", "{}", id);
+            assert_eq!(resolved.rule(), MatchRule::Normalized);
+        }
+
+        let exact = provider.resolve("claude-sonnet-4-20250514").unwrap();
+        assert_eq!(exact.rule(), MatchRule::Exact);
+    }
+
+    #[test]
+    fn test_resolve_aliases_and_families() {
+        let provider = PricingProvider::new();
+
+        let undated = provider.resolve("claude-3-5-haiku-latest").unwrap();
+        assert_eq!(undated.model(), "claude-3-5-haiku-20241022");
+        assert_eq!(undated.rule(), MatchRule::Undated);
+
+        let opus = provider.resolve("claude-opus-4-1-20250805").unwrap();
+        assert_eq!(opus.model(), "claude-opus-4-20250514");
+        assert_eq!(opus.rule(), MatchRule::Family);
+        assert_eq!(provider.get_model_weight("claude-opus-4-1-20250805"), 5.0);
+
+        let sonnet = provider.resolve("claude-sonnet-4-5").unwrap();
+        assert_eq!(sonnet.model(), "claude-sonnet-4-20250514");
+
+        // No haiku 4 is known, and an older generation's price is not a guess
+        assert!(provider.resolve("claude-haiku-4-5-20251001").is_none());
+        assert_eq!(provider.get_model_weight("claude-haiku-4-5-20251001"), 1.0);
+
+        assert!(provider.resolve("gpt-4").is_none());
+    }
+
+    #[test]
+    fn test_builtin_weights() {
+        let provider = PricingProvider::new();
+        assert_eq!(provider.get_model_weight("claude-opus-4-20250514"), 5.0);
+        assert_eq!(provider.get_model_weight("claude-3-5-haiku-20241022"), 0.2);
+        assert_eq!(provider.get_model_weight("claude-sonnet-4-20250514"), 1.0);
+        assert_eq!(provider.get_model_weight("unknown-model"), 1.0);
+    }
+
+    #[test]
+    fn test_merge_toml_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pricing.toml");
+        std::fs::write(
+            &path,
+            r#"
+[models."claude-opus-4-1-20250805"]
+input = 15.0
+output = 75.0
+weight = 5.0
+
+[models."claude-sonnet-4-20250514"]
+output = 20.0
+"#,
+        )
+        .unwrap();
+
+        let provider = PricingProvider::from_file(&path).unwrap();
+
+        let opus = provider.get_pricing("claude-opus-4-1-20250805").unwrap();
+        assert_eq!(opus.input_cost_per_token(), 15.0 / 1_000_000.0);
+        assert_eq!(opus.cache_creation_input_token_cost(), 18.75 / 1_000_000.0);
+        assert_eq!(opus.cache_read_input_token_cost(), 1.5 / 1_000_000.0);
+        assert_eq!(provider.get_model_weight("claude-opus-4-1-20250805"), 5.0);
+
+        let sonnet = provider.get_pricing("claude-sonnet-4-20250514").unwrap();
+        assert_eq!(sonnet.input_cost_per_token(), 3.0 / 1_000_000.0);
+        assert_eq!(sonnet.output_cost_per_token(), 20.0 / 1_000_000.0);
+    }
+
+    #[test]
+    fn test_long_context_tier() {
+        let provider = PricingProvider::new();
+        let model = "claude-sonnet-4-20250514";
+
+        let short = UsageEntry::new(Utc::now(), model.to_string(), 200_000, 1_000, 0, 0, 0.0);
+        let long = UsageEntry::new(
+            Utc::now(),
+            model.to_string(),
+            150_000,
+            1_000,
+            0,
+            60_000,
+            0.0,
+        );
+
+        // 200K input at $3/M plus 1K output at $15/M
+        assert_eq!(
+            provider.cost_for_entry(&short).unwrap(),
+            Usd::from_f64(0.615)
+        );
+        // 150K input at $6/M, 60K cache reads at $0.6/M, 1K output at $22.50/M
+        assert_eq!(
+            provider.cost_for_entry(&long).unwrap(),
+            Usd::from_f64(0.9585)
+        );
+    }
+
+    #[test]
+    fn test_tiers_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pricing.toml");
+        std::fs::write(
+            &path,
+            r#"
+[models."claude-opus-4-20250514"]
+input = 15.0
+
+[[models."claude-opus-4-20250514".tiers]]
+above = 100000
+input = 30.0
+output = 150.0
+"#,
+        )
+        .unwrap();
+
+        let provider = PricingProvider::from_file(&path).unwrap();
+        let pricing = provider.get_pricing("claude-opus-4-20250514").unwrap();
+        assert_eq!(pricing.tiers().len(), 1);
+        assert_eq!(
+            pricing.rates_for_prompt(100_001).input_cost_per_token(),
+            30.0 / 1_000_000.0
+        );
+        assert_eq!(
+            pricing.rates_for_prompt(100_000).input_cost_per_token(),
+            15.0 / 1_000_000.0
+        );
+
+        let sonnet = provider.get_pricing("claude-sonnet-4-20250514").unwrap();
+        assert_eq!(sonnet.tiers().len(), 1);
+    }
+
+    #[test]
+    fn test_historical_pricing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pricing.toml");
+        std::fs::write(
+            &path,
+            r#"
+[models."claude-3-5-haiku-20241022"]
+effective_until = "2025-01-01"
+input = 0.8
+"#,
+        )
+        .unwrap();
+
+        let provider = PricingProvider::from_file(&path).unwrap();
+        let model = "claude-3-5-haiku-20241022";
+        let before = Utc.with_ymd_and_hms(2024, 12, 31, 23, 0, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+
+        let old = provider.get_pricing_at(model, before).unwrap();
+        assert_eq!(old.input_cost_per_token(), 0.8 / 1_000_000.0);
+        assert_eq!(old.output_cost_per_token(), 5.0 / 1_000_000.0);
+        assert_eq!(
+            provider
+                .get_pricing_at(model, after)
+                .unwrap()
+                .input_cost_per_token(),
+            1.0 / 1_000_000.0
+        );
+
+        let entry = UsageEntry::new(before, model.to_string(), 1_000_000, 0, 0, 0, 0.0);
+        assert_eq!(provider.cost_for_entry(&entry).unwrap(), Usd::from_f64(0.8));
+    }
+
+    #[test]
+    fn test_merge_json_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pricing.json");
+        std::fs::write(
+            &path,
+            r#"{"models": {"claude-3-haiku-20240307": {"weight": 0.5}}}"#,
+        )
+        .unwrap();
+
+        let provider = PricingProvider::from_file(&path).unwrap();
+        assert_eq!(provider.get_model_weight("claude-3-haiku-20240307"), 0.5);
+    }
+
+    #[test]
+    fn test_new_model_requires_rates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pricing.json");
+        std::fs::write(&path, r#"{"models": {"new-model": {"input": 1.0}}}"#).unwrap();
+
+        assert!(PricingProvider::from_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_import_litellm_catalog() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model_prices_and_context_window.json");
+        std::fs::write(
+            &path,
+            r#"{
+  "sample_spec": {"max_tokens": "set to max_output_tokens if provider specifies it"},
+  "gpt-4o": {"litellm_provider": "openai", "input_cost_per_token": 2.5e-06, "output_cost_per_token": 1e-05},
+  "claude-sonnet-4-20250514": {
+    "litellm_provider": "anthropic",
+    "input_cost_per_token": 4e-06,
+    "output_cost_per_token": 1.6e-05,
+    "cache_creation_input_token_cost": 5e-06,
+    "cache_read_input_token_cost": 4e-07,
+    "input_cost_per_token_above_200k_tokens": 8e-06,
+    "output_cost_per_token_above_200k_tokens": 3e-05
+  },
+  "anthropic.claude-sonnet-4-20250514-v1:0": {
+    "litellm_provider": "bedrock",
+    "input_cost_per_token": 9e-06,
+    "output_cost_per_token": 9e-05
+  },
+  "us.anthropic.claude-sonnet-4-20250514-v1:0": {
+    "litellm_provider": "bedrock_converse",
+    "input_cost_per_token": 9.9e-06,
+    "output_cost_per_token": 9.9e-05
+  },
+  "anthropic.claude-3-7-sonnet-20250219-v1:0": {
+    "litellm_provider": "bedrock_converse",
+    "input_cost_per_token": 3.3e-06,
+    "output_cost_per_token": 1.65e-05
+  },
+  "vertex_ai/claude-sonnet-4@20250514": {
+    "litellm_provider": "vertex_ai-anthropic_models",
+    "input_cost_per_token": 5e-06,
+    "output_cost_per_token": 2e-05
+  },
+  "openrouter/anthropic/claude-sonnet-4": {
+    "litellm_provider": "openrouter",
+    "input_cost_per_token": 7e-06,
+    "output_cost_per_token": 7e-05
+  }
+}"#,
+        )
+        .unwrap();
+
+        let mut provider = PricingProvider::new();
+        assert_eq!(provider.import_litellm(&path).unwrap(), 4);
+        assert!(provider.resolve("gpt-4o").is_none());
+
+        // Both a Bedrock and a direct entry: the direct price is the list price
+        let sonnet = provider.get_pricing("claude-sonnet-4-20250514").unwrap();
+        assert_eq!(sonnet.input_cost_per_token(), 4e-06);
+        assert_eq!(sonnet.cache_creation_input_token_cost(), 5e-06);
+        assert_eq!(sonnet.cache_read_input_token_cost(), 4e-07);
+        assert_eq!(
+            sonnet.rates_for_prompt(300_000).output_cost_per_token(),
+            3e-05
+        );
+
+        // Only a Bedrock entry: its price is not stored under the Anthropic id
+        assert!(!provider
+            .supported_models()
+            .iter()
+            .any(|model| model.as_str() == "claude-3-7-sonnet-20250219"));
+        let resolved = provider.resolve("claude-3-7-sonnet-20250219").unwrap();
+        assert_ne!(resolved.pricing().output_cost_per_token(), 1.65e-05);
+
+        // Provider-prefixed entries fill their profiles, global before regional
+        let now = Utc::now();
+        let bedrock = provider
+            .resolve_in("bedrock", "claude-sonnet-4-20250514", now)
+            .unwrap();
+        assert_eq!(bedrock.pricing().input_cost_per_token(), 9e-06);
+        let bedrock = provider
+            .resolve_in("bedrock", "claude-3-7-sonnet-20250219", now)
+            .unwrap();
+        assert_eq!(bedrock.pricing().output_cost_per_token(), 1.65e-05);
+        let vertex = provider
+            .resolve_in("vertex", "claude-sonnet-4-20250514", now)
+            .unwrap();
+        assert_eq!(vertex.pricing().input_cost_per_token(), 5e-06);
+    }
+
+    #[test]
+    fn test_profile_rates_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pricing.toml");
+        std::fs::write(
+            &path,
+            r#"
+[profiles.bedrock.models."claude-sonnet-4-20250514"]
+input = 3.3
+output = 16.5
+
+[profiles.corp.models."claude-3-5-haiku-20241022"]
+input = 0.5
+"#,
+        )
+        .unwrap();
+
+        let provider = PricingProvider::from_file(&path).unwrap();
+        assert_eq!(
+            provider.profile_names(),
+            vec!["anthropic", "bedrock", "corp", "vertex"]
+        );
+
+        let now = Utc::now();
+        let model = "us.anthropic.claude-sonnet-4-20250514-v1:0";
+        let bedrock = provider.resolve_in("bedrock", model, now).unwrap();
+        assert_eq!(bedrock.pricing().input_cost_per_token(), 3.3 / 1_000_000.0);
+        assert_eq!(
+            bedrock.pricing().cache_read_input_token_cost(),
+            0.3 / 1_000_000.0
+        );
+        let vertex = provider.resolve_in("vertex", model, now).unwrap();
+        assert_eq!(vertex.pricing().input_cost_per_token(), 3.0 / 1_000_000.0);
+
+        let haiku = provider
+            .resolve_in("corp", "claude-3-5-haiku-20241022", now)
+            .unwrap();
+        assert_eq!(haiku.pricing().input_cost_per_token(), 0.5 / 1_000_000.0);
+        assert_eq!(haiku.pricing().output_cost_per_token(), 5.0 / 1_000_000.0);
+
+        assert_eq!(provider.profile_for(model, None), "bedrock");
+        assert_eq!(
+            provider.profile_for("claude-sonnet-4-20250514", None),
+            "anthropic"
+        );
+        assert_eq!(provider.profile_for(model, Some("corp")), "corp");
+
+        let mut provider = provider;
+        assert!(provider.set_default_profile("nope").is_err());
+        provider.set_default_profile("vertex").unwrap();
+        assert_eq!(provider.profile_for(model, None), "vertex");
+    }
+}
@@ -0,0 +1,240 @@
+use crate::cost::Usd;
+use crate::data_structures::UsageEntry;
+use crate::limits::LimitEvent;
+use crate::pricing::{MatchRule, ResolvedModel};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Why a non-empty transcript line produced no usage entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SkipReason {
+    InvalidJson,
+    MissingUsage,
+    InvalidTimestamp,
+}
+
+impl SkipReason {
+    pub fn description(&self) -> &'static str {
+        match self {
+            SkipReason::InvalidJson => "invalid JSON",
+            SkipReason::MissingUsage => "no usage data",
+            SkipReason::InvalidTimestamp => "missing or invalid timestamp",
+        }
+    }
+}
+
+/// An entry whose logged cost differs from the cost computed from its tokens.
+#[derive(Debug, Clone)]
+pub struct CostDiscrepancy {
+    timestamp: DateTime<Utc>,
+    model: String,
+    message_id: Option<String>,
+    logged_cost: Usd,
+    calculated_cost: Usd,
+}
+
+impl CostDiscrepancy {
+    /// The discrepancy of an audited entry, if its costs differ by more than
+    /// `tolerance` dollars.
+    pub fn from_entry(entry: &UsageEntry, tolerance: f64) -> Option<Self> {
+        let logged_cost = entry.logged_cost()?;
+        let calculated_cost = entry.calculated_cost()?;
+
+        (logged_cost.abs_diff(calculated_cost) > Usd::from_f64(tolerance)).then(|| Self {
+            timestamp: entry.timestamp(),
+            model: entry.model().to_string(),
+            message_id: entry.message_id().map(str::to_string),
+            logged_cost,
+            calculated_cost,
+        })
+    }
+
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    pub fn message_id(&self) -> Option<&str> {
+        self.message_id.as_deref()
+    }
+
+    pub fn logged_cost_usd(&self) -> f64 {
+        self.logged_cost.as_f64()
+    }
+
+    pub fn calculated_cost_usd(&self) -> f64 {
+        self.calculated_cost.as_f64()
+    }
+
+    /// Logged minus computed cost.
+    pub fn difference(&self) -> Usd {
+        self.logged_cost - self.calculated_cost
+    }
+}
+
+/// Diagnostics collected while loading transcripts.
+#[derive(Debug, Clone, Default)]
+pub struct LoadReport {
+    files_scanned: usize,
+    lines_read: usize,
+    skipped_lines: BTreeMap<SkipReason, usize>,
+    unreadable_files: Vec<(PathBuf, String)>,
+    unknown_models: BTreeSet<String>,
+    unpriced_entries: usize,
+    resolved_models: BTreeMap<String, (String, MatchRule)>,
+    duplicates_removed: usize,
+    costs_audited: usize,
+    cost_discrepancies: Vec<CostDiscrepancy>,
+    limit_events: Vec<LimitEvent>,
+}
+
+impl LoadReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_file_scanned(&mut self) {
+        self.files_scanned += 1;
+    }
+
+    pub fn record_line_read(&mut self) {
+        self.lines_read += 1;
+    }
+
+    pub fn record_skipped_line(&mut self, reason: SkipReason) {
+        *self.skipped_lines.entry(reason).or_insert(0) += 1;
+    }
+
+    pub fn record_unreadable_file(&mut self, path: &Path, error: &anyhow::Error) {
+        if !self.unreadable_files.iter().any(|(p, _)| p == path) {
+            self.unreadable_files
+                .push((path.to_path_buf(), format!("{:#}", error)));
+        }
+    }
+
+    pub fn record_unknown_model(&mut self, model: &str) {
+        if !self.unknown_models.contains(model) {
+            self.unknown_models.insert(model.to_string());
+        }
+    }
+
+    /// Records an entry of an unknown model that had no logged cost either,
+    /// so it counts as $0.
+    pub fn record_unpriced_entry(&mut self) {
+        self.unpriced_entries += 1;
+    }
+
+    /// Records a model that was priced through an alias or fallback rule.
+    pub fn record_resolved_model(&mut self, model: &str, resolved: &ResolvedModel) {
+        if !self.resolved_models.contains_key(model) {
+            self.resolved_models.insert(
+                model.to_string(),
+                (resolved.model().to_string(), resolved.rule()),
+            );
+        }
+    }
+
+    /// Records an entry checked in audit mode and whether its costs disagree.
+    pub fn record_cost_audit(&mut self, discrepancy: Option<CostDiscrepancy>) {
+        self.costs_audited += 1;
+        self.cost_discrepancies.extend(discrepancy);
+    }
+
+    /// Records a usage-limit notice, once however many transcripts repeat it.
+    pub fn record_limit_event(&mut self, event: LimitEvent) {
+        if !self.limit_events.contains(&event) {
+            self.limit_events.push(event);
+            self.limit_events.sort_by_key(|event| event.timestamp());
+        }
+    }
+
+    pub fn record_duplicates_removed(&mut self, count: usize) {
+        self.duplicates_removed += count;
+    }
+
+    /// Adds the counts of `other` to this report.
+    pub fn merge(&mut self, other: LoadReport) {
+        self.files_scanned += other.files_scanned;
+        self.lines_read += other.lines_read;
+        for (reason, count) in other.skipped_lines {
+            *self.skipped_lines.entry(reason).or_insert(0) += count;
+        }
+        for (path, error) in other.unreadable_files {
+            if !self.unreadable_files.iter().any(|(p, _)| *p == path) {
+                self.unreadable_files.push((path, error));
+            }
+        }
+        self.unknown_models.extend(other.unknown_models);
+        self.unpriced_entries += other.unpriced_entries;
+        for (model, resolved) in other.resolved_models {
+            self.resolved_models.entry(model).or_insert(resolved);
+        }
+        self.duplicates_removed += other.duplicates_removed;
+        self.costs_audited += other.costs_audited;
+        self.cost_discrepancies.extend(other.cost_discrepancies);
+        for event in other.limit_events {
+            self.record_limit_event(event);
+        }
+    }
+
+    pub fn files_scanned(&self) -> usize {
+        self.files_scanned
+    }
+
+    pub fn lines_read(&self) -> usize {
+        self.lines_read
+    }
+
+    pub fn skipped_lines(&self) -> &BTreeMap<SkipReason, usize> {
+        &self.skipped_lines
+    }
+
+    pub fn skipped_count(&self, reason: SkipReason) -> usize {
+        self.skipped_lines.get(&reason).copied().unwrap_or(0)
+    }
+
+    pub fn total_skipped(&self) -> usize {
+        self.skipped_lines.values().sum()
+    }
+
+    pub fn unreadable_files(&self) -> &[(PathBuf, String)] {
+        &self.unreadable_files
+    }
+
+    pub fn unknown_models(&self) -> &BTreeSet<String> {
+        &self.unknown_models
+    }
+
+    /// Entries of unknown models that were counted as $0.
+    pub fn unpriced_entries(&self) -> usize {
+        self.unpriced_entries
+    }
+
+    /// Models that did not match a pricing entry exactly, with the entry
+    /// and rule they resolved to.
+    pub fn resolved_models(&self) -> &BTreeMap<String, (String, MatchRule)> {
+        &self.resolved_models
+    }
+
+    pub fn duplicates_removed(&self) -> usize {
+        self.duplicates_removed
+    }
+
+    /// Number of entries whose costs were compared in audit mode.
+    pub fn costs_audited(&self) -> usize {
+        self.costs_audited
+    }
+
+    pub fn cost_discrepancies(&self) -> &[CostDiscrepancy] {
+        &self.cost_discrepancies
+    }
+
+    /// Usage-limit notices, oldest first.
+    pub fn limit_events(&self) -> &[LimitEvent] {
+        &self.limit_events
+    }
+}
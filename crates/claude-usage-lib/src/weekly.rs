@@ -0,0 +1,256 @@
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc, Weekday};
+use std::fmt;
+use std::str::FromStr;
+
+/// Day and time (UTC) at which the weekly window starts over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeeklyReset {
+    weekday: Weekday,
+    time: NaiveTime,
+}
+
+impl WeeklyReset {
+    pub fn new(weekday: Weekday, time: NaiveTime) -> Self {
+        Self { weekday, time }
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        self.weekday
+    }
+
+    pub fn time(&self) -> NaiveTime {
+        self.time
+    }
+
+    /// The most recent reset at or before `timestamp`.
+    pub fn last_reset(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let days_since = (timestamp.weekday().num_days_from_monday() + 7
+            - self.weekday.num_days_from_monday())
+            % 7;
+        let reset = (timestamp.date_naive() - Duration::days(days_since as i64))
+            .and_time(self.time)
+            .and_utc();
+
+        if reset > timestamp {
+            reset - Duration::weeks(1)
+        } else {
+            reset
+        }
+    }
+}
+
+impl Default for WeeklyReset {
+    /// Monday 00:00 UTC.
+    fn default() -> Self {
+        Self::new(Weekday::Mon, NaiveTime::MIN)
+    }
+}
+
+impl fmt::Display for WeeklyReset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.weekday, self.time.format("%H:%M"))
+    }
+}
+
+impl FromStr for WeeklyReset {
+    type Err = anyhow::Error;
+
+    /// Parses a weekday with an optional `HH:MM`, e.g. `mon` or `thu 18:00`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let weekday = parts
+            .next()
+            .and_then(|day| day.parse::<Weekday>().ok())
+            .ok_or_else(|| {
+                anyhow::anyhow!("Invalid weekly reset '{}' (expected e.g. 'mon 00:00')", s)
+            })?;
+        let time = match parts.next() {
+            Some(time) => NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| {
+                anyhow::anyhow!("Invalid weekly reset time '{}' (expected HH:MM)", time)
+            })?,
+            None => NaiveTime::MIN,
+        };
+
+        Ok(Self::new(weekday, time))
+    }
+}
+
+/// Weekly budgets, in the monitor's limit-model units, on top of the
+/// per-session limits. A budget left unset is not enforced.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct WeeklyConfig {
+    reset: WeeklyReset,
+    all_models_budget: Option<f64>,
+    opus_budget: Option<f64>,
+}
+
+impl WeeklyConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_reset(mut self, reset: WeeklyReset) -> Self {
+        self.reset = reset;
+        self
+    }
+
+    pub fn with_all_models_budget(mut self, budget: f64) -> Self {
+        self.all_models_budget = Some(budget);
+        self
+    }
+
+    pub fn with_opus_budget(mut self, budget: f64) -> Self {
+        self.opus_budget = Some(budget);
+        self
+    }
+
+    pub fn reset(&self) -> WeeklyReset {
+        self.reset
+    }
+
+    pub fn all_models_budget(&self) -> Option<f64> {
+        self.all_models_budget
+    }
+
+    pub fn opus_budget(&self) -> Option<f64> {
+        self.opus_budget
+    }
+
+    /// Start and end (the next reset) of the week containing `timestamp`.
+    pub fn window_for(&self, timestamp: DateTime<Utc>) -> (DateTime<Utc>, DateTime<Utc>) {
+        let start = self.reset.last_reset(timestamp);
+        (start, start + Duration::weeks(1))
+    }
+}
+
+/// Usage of one budget within the current weekly window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeeklyBudget {
+    used: f64,
+    budget: Option<f64>,
+    projected_exhaustion: Option<DateTime<Utc>>,
+}
+
+impl WeeklyBudget {
+    pub fn new(
+        used: f64,
+        budget: Option<f64>,
+        projected_exhaustion: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            used,
+            budget,
+            projected_exhaustion,
+        }
+    }
+
+    pub fn used(&self) -> f64 {
+        self.used
+    }
+
+    pub fn budget(&self) -> Option<f64> {
+        self.budget
+    }
+
+    /// Share of the budget used, or `None` without a budget.
+    pub fn percentage(&self) -> Option<f64> {
+        self.budget
+            .filter(|budget| *budget > 0.0)
+            .map(|budget| self.used / budget * 100.0)
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.budget.is_some_and(|budget| self.used >= budget)
+    }
+
+    /// When the budget runs out at the week's average rate so far, if that
+    /// happens before the window resets.
+    pub fn projected_exhaustion(&self) -> Option<DateTime<Utc>> {
+        self.projected_exhaustion
+    }
+}
+
+/// Usage against the weekly budgets for the window containing a given time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeeklyUsage {
+    window_start: DateTime<Utc>,
+    resets_at: DateTime<Utc>,
+    all_models: WeeklyBudget,
+    opus: WeeklyBudget,
+}
+
+impl WeeklyUsage {
+    pub fn new(
+        window_start: DateTime<Utc>,
+        resets_at: DateTime<Utc>,
+        all_models: WeeklyBudget,
+        opus: WeeklyBudget,
+    ) -> Self {
+        Self {
+            window_start,
+            resets_at,
+            all_models,
+            opus,
+        }
+    }
+
+    pub fn window_start(&self) -> DateTime<Utc> {
+        self.window_start
+    }
+
+    pub fn resets_at(&self) -> DateTime<Utc> {
+        self.resets_at
+    }
+
+    pub fn all_models(&self) -> &WeeklyBudget {
+        &self.all_models
+    }
+
+    pub fn opus(&self) -> &WeeklyBudget {
+        &self.opus
+    }
+
+    /// The earlier of the two projected exhaustion times.
+    pub fn projected_exhaustion(&self) -> Option<DateTime<Utc>> {
+        match (
+            self.all_models.projected_exhaustion(),
+            self.opus.projected_exhaustion(),
+        ) {
+            (Some(all), Some(opus)) => Some(all.min(opus)),
+            (all, opus) => all.or(opus),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_weekly_window_and_reset_parsing() {
+        let reset: WeeklyReset = "thu 18:00".parse().unwrap();
+        assert_eq!(reset.to_string(), "Thu 18:00");
+        assert!("someday".parse::<WeeklyReset>().is_err());
+        assert!("mon 25:00".parse::<WeeklyReset>().is_err());
+
+        let config = WeeklyConfig::new().with_reset(reset);
+        // 2025-01-02 is a Thursday
+        let before_reset = Utc.with_ymd_and_hms(2025, 1, 2, 17, 0, 0).unwrap();
+        let after_reset = Utc.with_ymd_and_hms(2025, 1, 2, 19, 0, 0).unwrap();
+        let this_week = Utc.with_ymd_and_hms(2025, 1, 2, 18, 0, 0).unwrap();
+
+        assert_eq!(
+            config.window_for(before_reset),
+            (this_week - Duration::weeks(1), this_week)
+        );
+        assert_eq!(
+            config.window_for(after_reset),
+            (this_week, this_week + Duration::weeks(1))
+        );
+        assert_eq!(
+            WeeklyConfig::new().window_for(after_reset).0,
+            Utc.with_ymd_and_hms(2024, 12, 30, 0, 0, 0).unwrap()
+        );
+    }
+}
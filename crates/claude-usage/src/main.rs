@@ -1,8 +1,13 @@
-use anyhow::Result;
-use chrono::{DateTime, Timelike, Utc};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use clap::Parser;
 use claude_usage_monitor::prelude::*;
-use claude_usage_monitor::{ClaudePlan, UsageMonitor};
+use claude_usage_monitor::{
+    builtin_limit_model, discover_data_roots, ClaudePlan, ConfiguredLimitModel, CostMode, Currency,
+    DataLoader, ExchangeRates, LimitEvent, LimitModel, LimitSource, PlanRegistry, PricingProvider,
+    SessionConfig, SessionRounding, UsageArchive, UsageMonitor, WeeklyConfig, WeeklyReset,
+    WeeklyUsage,
+};
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use ratatui::{
     layout::{Constraint, Direction, Layout},
@@ -13,15 +18,19 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::sync::mpsc;
 use tokio::time::interval;
 
+mod watcher;
 mod widgets;
+use watcher::DataWatcher;
 use widgets::*;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum PopupType {
     CurrentBlock,
     LifetimeStats,
+    LoadReport,
 }
 
 #[derive(Parser, Debug)]
@@ -30,11 +39,84 @@ struct Args {
     #[arg(short = 'v')]
     verbose: bool,
 
+    /// Plan: pro, max5, max20, auto, or a custom plan from the plans file
     #[arg(short = 'p', long = "plan", default_value = "pro")]
     plan: String,
 
+    /// Custom plan definitions (TOML or JSON) with token and cost limits
+    #[arg(long = "plans")]
+    plans: Option<PathBuf>,
+
     #[arg(short = 'd', long = "data-dir")]
     data_dir: Option<String>,
+
+    /// Reload when transcripts change instead of polling every 5 seconds
+    #[arg(short = 'w', long = "watch")]
+    watch: bool,
+
+    /// Number of threads used to parse transcripts (defaults to all cores)
+    #[arg(short = 'j', long = "jobs")]
+    jobs: Option<usize>,
+
+    /// Pricing file (TOML or JSON) merged over the built-in model rates
+    #[arg(long = "pricing")]
+    pricing: Option<PathBuf>,
+
+    /// LiteLLM model_prices_and_context_window.json to import Claude prices from
+    #[arg(long = "catalog")]
+    catalog: Option<PathBuf>,
+
+    /// Pricing profile for all data: anthropic, bedrock, vertex or one from the pricing file
+    #[arg(long = "pricing-profile")]
+    pricing_profile: Option<String>,
+
+    /// Pricing profile for one data root, as PATH=PROFILE (repeatable)
+    #[arg(long = "root-profile", value_name = "PATH=PROFILE")]
+    root_profiles: Vec<String>,
+
+    /// Cost source: auto, calculate, display or audit
+    #[arg(long = "cost-mode", default_value = "auto")]
+    cost_mode: CostMode,
+
+    /// How usage counts against limits: weighted, raw, with-cache, cost or configured
+    #[arg(long = "limit-model", default_value = "weighted")]
+    limit_model: String,
+
+    /// Limit model config file (implies --limit-model configured)
+    #[arg(long = "limit-config")]
+    limit_config: Option<PathBuf>,
+
+    /// Currency to show costs in, e.g. EUR or CHF (needs an exchange-rate file)
+    #[arg(long = "currency", default_value = "USD")]
+    currency: String,
+
+    /// Exchange-rate file (TOML or JSON) with dated rates from USD
+    #[arg(long = "rates")]
+    rates: Option<PathBuf>,
+
+    /// Length of a usage session in hours
+    #[arg(long = "session-hours", default_value_t = 5.0)]
+    session_hours: f64,
+
+    /// Where sessions start: hour (on the hour), exact (first message) or daily:HH:MM (UTC)
+    #[arg(long = "session-rounding", default_value = "hour")]
+    session_rounding: SessionRounding,
+
+    /// Weekly budget for all models, in limit-model units
+    #[arg(long = "weekly-limit")]
+    weekly_limit: Option<f64>,
+
+    /// Weekly budget for Opus models, in limit-model units
+    #[arg(long = "weekly-opus-limit")]
+    weekly_opus_limit: Option<f64>,
+
+    /// When the weekly window resets (UTC), e.g. "mon 00:00"
+    #[arg(long = "weekly-reset", default_value = "mon 00:00")]
+    weekly_reset: WeeklyReset,
+
+    /// Don't read or write the local usage archive
+    #[arg(long = "no-archive")]
+    no_archive: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -50,6 +132,58 @@ fn get_config_path() -> Result<PathBuf> {
     Ok(parent_dir.join(".usage.json"))
 }
 
+fn load_limit_model(name: &str, config_path: Option<&Path>) -> Result<Box<dyn LimitModel>> {
+    if let Some(path) = config_path {
+        return Ok(Box::new(ConfiguredLimitModel::from_file(path)?));
+    }
+
+    if name == "configured" {
+        return match ConfiguredLimitModel::default_config_path() {
+            Some(path) => Ok(Box::new(ConfiguredLimitModel::from_file(path)?)),
+            None => Err(anyhow::anyhow!(
+                "No limit model config found in ~/.config/claude-usage/limits.toml"
+            )),
+        };
+    }
+
+    builtin_limit_model(name).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown limit model '{}' (expected weighted, raw, with-cache, cost or configured)",
+            name
+        )
+    })
+}
+
+fn load_currency(code: &str, rates_path: Option<&Path>) -> Result<Currency> {
+    if code.eq_ignore_ascii_case("USD") && rates_path.is_none() {
+        return Ok(Currency::usd());
+    }
+
+    let rates = match rates_path {
+        Some(path) => ExchangeRates::from_file(path)?,
+        None => match ExchangeRates::default_config_path() {
+            Some(path) => ExchangeRates::from_file(path)?,
+            None => {
+                return Err(anyhow::anyhow!(
+                    "No exchange rates found in ~/.config/claude-usage/rates.toml"
+                ))
+            }
+        },
+    };
+
+    rates.currency(code)
+}
+
+fn load_plan_registry(plans_path: Option<&Path>) -> Result<PlanRegistry> {
+    match plans_path
+        .map(Path::to_path_buf)
+        .or_else(PlanRegistry::default_config_path)
+    {
+        Some(path) => PlanRegistry::from_file(path),
+        None => Ok(PlanRegistry::new()),
+    }
+}
+
 fn load_config() -> Result<UsageConfig> {
     let config_path = get_config_path()?;
 
@@ -72,20 +206,13 @@ fn save_config(config: &UsageConfig) -> Result<()> {
     Ok(())
 }
 
-fn discover_claude_data_paths() -> Vec<std::path::PathBuf> {
-    let standard_paths = ["~/.claude/projects", "~/.config/claude/projects"];
+/// How often watch mode reloads anyway, in case an event was missed.
+const WATCH_FALLBACK_INTERVAL: Duration = Duration::from_secs(60);
 
-    let mut discovered_paths = Vec::new();
-
-    for path_str in &standard_paths {
-        let path = shellexpand::tilde(path_str);
-        let path = Path::new(path.as_ref());
-        if path.exists() && path.is_dir() {
-            discovered_paths.push(path.to_path_buf());
-        }
-    }
-
-    discovered_paths
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RefreshMode {
+    Polling,
+    Watching,
 }
 
 pub struct AppState {
@@ -97,12 +224,16 @@ pub struct AppState {
     pub data_loaded: bool,
     pub error_message: Option<String>,
     pub active_popup: Option<PopupType>,
+    pub refresh_mode: RefreshMode,
+    /// Why `--watch` fell back to polling, if it did.
+    pub watch_error: Option<String>,
+    pub currency: Currency,
 }
 
 impl AppState {
-    fn new(plan: ClaudePlan) -> Self {
+    fn new(plan: ClaudePlan, usage_monitor: UsageMonitor, currency: Currency) -> Self {
         Self {
-            usage_monitor: UsageMonitor::new(),
+            usage_monitor,
             plan,
             last_update: Utc::now(),
             is_loading: false,
@@ -110,6 +241,9 @@ impl AppState {
             data_loaded: false,
             error_message: None,
             active_popup: None,
+            refresh_mode: RefreshMode::Polling,
+            watch_error: None,
+            currency,
         }
     }
 
@@ -120,51 +254,32 @@ impl AppState {
         let result = if let Some(data_path) = data_dir {
             // Load from specific directory/file
             let path = Path::new(&data_path);
-            if path.is_file() {
-                self.usage_monitor.load_data(&data_path)
-            } else if path.is_dir() {
-                self.usage_monitor.load_directory(&data_path)
+            if path.is_file() || path.is_dir() {
+                self.usage_monitor.refresh(&data_path)
             } else {
                 return Err(anyhow::anyhow!("Path does not exist: {}", data_path));
             }
         } else {
-            // Auto-discover Claude data paths
-            let claude_paths = discover_claude_data_paths();
+            // Auto-discover Claude data roots and merge all of them
+            let claude_roots = discover_data_roots();
 
-            if claude_paths.is_empty() {
+            if claude_roots.is_empty() {
                 return Err(anyhow::anyhow!(
-                    "No Claude data directories found in standard locations:\n  ~/.claude/projects\n  ~/.config/claude/projects"
+                    "No Claude data directories found in standard locations:\n  ~/.claude/projects\n  ~/.config/claude/projects\nSet CLAUDE_CONFIG_DIR to add other locations"
                 ));
             }
 
-            let mut loaded_any = false;
-            let mut last_error = None;
-
-            for claude_path in &claude_paths {
-                match self.usage_monitor.load_directory(claude_path) {
-                    Ok(_) => {
-                        if !self.usage_monitor.is_empty() {
-                            loaded_any = true;
-                            break;
-                        }
+            self.usage_monitor
+                .refresh_roots(&claude_roots)
+                .and_then(|_| {
+                    if self.usage_monitor.is_empty() {
+                        Err(anyhow::anyhow!(
+                            "No usage data found in any Claude directories"
+                        ))
+                    } else {
+                        Ok(())
                     }
-                    Err(e) => {
-                        last_error = Some(e);
-                    }
-                }
-            }
-
-            if !loaded_any {
-                if let Some(e) = last_error {
-                    return Err(e);
-                } else {
-                    return Err(anyhow::anyhow!(
-                        "No usage data found in any Claude directories"
-                    ));
-                }
-            }
-
-            Ok(())
+                })
         };
 
         match &result {
@@ -205,7 +320,29 @@ impl AppState {
     }
 
     pub fn get_usage_percentage(&self) -> f64 {
-        self.usage_monitor.get_current_block_percentage(self.plan)
+        self.usage_monitor.get_current_block_percentage(&self.plan)
+    }
+
+    /// Per-block limit used as the denominator, estimated for the auto plan.
+    pub fn get_token_limit(&self) -> u64 {
+        self.usage_monitor.get_token_limit(&self.plan) as u64
+    }
+
+    /// How the auto plan's limit was estimated, for display next to it.
+    pub fn get_limit_estimate_label(&self) -> Option<String> {
+        if self.plan != ClaudePlan::Auto {
+            return None;
+        }
+
+        Some(match self.usage_monitor.estimate_token_limit() {
+            Some(estimate) => match estimate.source() {
+                LimitSource::LimitEvents { blocks } => {
+                    format!("P90 of {} limit hits", blocks)
+                }
+                LimitSource::PeakBlock => "peak block".to_string(),
+            },
+            None => "no history yet".to_string(),
+        })
     }
 
     pub fn get_current_tokens(&self) -> u64 {
@@ -221,7 +358,7 @@ impl AppState {
         self.usage_monitor.get_total_weighted_tokens() as u64
     }
 
-    pub fn get_lifetime_percentage(&self, plan: ClaudePlan) -> f64 {
+    pub fn get_lifetime_percentage(&self, plan: &ClaudePlan) -> f64 {
         self.usage_monitor.get_plan_usage_percentage(plan)
     }
 
@@ -234,6 +371,45 @@ impl AppState {
         self.usage_monitor.get_current_block_cost()
     }
 
+    pub fn format_total_cost(&self) -> String {
+        let converted = self.usage_monitor.get_total_cost_in(&self.currency);
+        self.format_cost(self.get_total_cost(), converted)
+    }
+
+    pub fn format_profile_cost(&self, profile: &str, usd: f64) -> String {
+        let converted = self
+            .usage_monitor
+            .get_profile_cost_in(profile, &self.currency);
+        self.format_cost(usd, converted)
+    }
+
+    pub fn format_current_block_cost(&self) -> String {
+        let converted = self.usage_monitor.get_current_block_cost_in(&self.currency);
+        let cost = self.format_cost(self.get_current_block_cost(), converted);
+
+        match (
+            self.plan.cost_cap(),
+            self.usage_monitor
+                .get_current_block_cost_percentage(&self.plan),
+        ) {
+            (Some(cap), Some(percentage)) => {
+                format!("{} of ${:.2} cap ({:.1}%)", cost, cap.as_f64(), percentage)
+            }
+            _ => cost,
+        }
+    }
+
+    /// Shows a converted cost with the dollar amount alongside, or just the
+    /// dollars when the display currency is USD.
+    pub fn format_cost(&self, usd: f64, converted: Option<f64>) -> String {
+        match converted {
+            Some(amount) if !self.currency.is_usd() => {
+                format!("{}{:.3} (${:.3})", self.currency.symbol(), amount, usd)
+            }
+            _ => format!("${:.3}", usd),
+        }
+    }
+
     pub fn get_current_block_duration(&self) -> f64 {
         self.usage_monitor.get_current_block_duration()
     }
@@ -251,10 +427,49 @@ impl AppState {
         self.usage_monitor.get_peak_burn_rate()
     }
 
+    /// The limit notice logged in the current block, with the units used
+    /// when it was hit.
+    pub fn get_current_block_limit_hit(&self) -> Option<(&LimitEvent, f64)> {
+        let block = self.usage_monitor.get_session_blocks().last()?;
+        if !block.hit_limit() {
+            return None;
+        }
+        self.usage_monitor.get_limit_hits().pop()
+    }
+
+    pub fn get_weekly_usage(&self) -> WeeklyUsage {
+        self.usage_monitor.get_weekly_usage()
+    }
+
+    /// Time until `time` as `2d 4h` or `3:05`.
+    pub fn format_time_until(&self, time: DateTime<Utc>) -> String {
+        let total_seconds = (time - Utc::now()).num_seconds().max(0);
+        let days = total_seconds / 86_400;
+        let hours = (total_seconds % 86_400) / 3600;
+        let minutes = (total_seconds % 3600) / 60;
+
+        if days > 0 {
+            format!("{}d {}h", days, hours)
+        } else {
+            format!("{}:{:02}", hours, minutes)
+        }
+    }
+
+    /// Session length for labels, e.g. `5h` or `90m`.
+    pub fn get_session_length_formatted(&self) -> String {
+        let minutes = self.usage_monitor.session_config().duration().num_minutes();
+        if minutes % 60 == 0 {
+            format!("{}h", minutes / 60)
+        } else {
+            format!("{}m", minutes)
+        }
+    }
+
     pub fn get_time_to_reset_formatted(&self) -> (String, f64) {
         let now = Utc::now();
+        let session_config = self.usage_monitor.session_config();
 
-        // Get the current 5-hour session block
+        // Get the current session block
         if let Some(current_block) = self.usage_monitor.get_session_blocks().last() {
             if !current_block.is_empty() && now < current_block.end_time() {
                 // We're in an active session block
@@ -263,9 +478,9 @@ impl AppState {
                 let hours = total_seconds / 3600;
                 let minutes = (total_seconds % 3600) / 60;
 
-                // Calculate percentage based on 5-hour session
+                // Calculate percentage based on the configured session length
                 let elapsed = now - current_block.start_time();
-                let session_duration = chrono::Duration::hours(5).num_seconds();
+                let session_duration = session_config.duration().num_seconds().max(1);
                 let elapsed_percentage =
                     1.0 - (elapsed.num_seconds() as f64 / session_duration as f64);
 
@@ -276,16 +491,8 @@ impl AppState {
             }
         }
 
-        // No active session or no data - calculate next 5-hour window
-        let current_hour = now
-            .with_minute(0)
-            .unwrap()
-            .with_second(0)
-            .unwrap()
-            .with_nanosecond(0)
-            .unwrap();
-        let next_session_start = current_hour + chrono::Duration::hours(1);
-        let next_session_end = next_session_start + chrono::Duration::hours(5);
+        // No active session or no data - show the window a message sent now would open
+        let (_, next_session_end) = session_config.window_for(now);
 
         let remaining = next_session_end - now;
         let total_seconds = remaining.num_seconds().max(0);
@@ -303,8 +510,13 @@ pub struct App {
 }
 
 impl App {
-    pub fn new(plan: ClaudePlan, data_dir: Option<String>) -> Self {
-        let mut app_state = AppState::new(plan);
+    pub fn new(
+        plan: ClaudePlan,
+        data_dir: Option<String>,
+        usage_monitor: UsageMonitor,
+        currency: Currency,
+    ) -> Self {
+        let mut app_state = AppState::new(plan, usage_monitor, currency);
 
         // Try to load data initially
         if let Err(e) = app_state.load_data(data_dir.clone()) {
@@ -321,21 +533,68 @@ impl App {
         &mut self,
         terminal: &mut DefaultTerminal,
         data_dir: Option<String>,
+        watch: bool,
     ) -> Result<()> {
         let state_clone = Arc::clone(&self.state);
         let data_dir_clone = data_dir.clone();
 
-        tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(5));
-            loop {
-                interval.tick().await;
-
-                if let Ok(mut state) = state_clone.lock() {
-                    // Reload data every 5 seconds
-                    let _ = state.load_data(data_dir_clone.clone());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let data_watcher = if watch {
+            let paths = watcher::watch_paths(data_dir.as_deref(), discover_data_roots());
+            match DataWatcher::spawn(&paths, tx) {
+                Ok(data_watcher) => Some(data_watcher),
+                Err(e) => {
+                    if let Ok(mut state) = self.state.lock() {
+                        state.watch_error = Some(e.to_string());
+                    }
+                    None
                 }
             }
-        });
+        } else {
+            None
+        };
+
+        if let Some(data_watcher) = data_watcher {
+            if let Ok(mut state) = self.state.lock() {
+                state.refresh_mode = RefreshMode::Watching;
+            }
+
+            tokio::spawn(async move {
+                // The watcher stops notifying when dropped, so the task owns it
+                let _data_watcher = data_watcher;
+                // Events can be missed or coalesced (network filesystems,
+                // editors that save by renaming), so also reload on a slow timer
+                let mut fallback = interval(WATCH_FALLBACK_INTERVAL);
+                fallback.tick().await;
+
+                loop {
+                    tokio::select! {
+                        reload = watcher::next_reload(&mut rx) => {
+                            if reload.is_none() {
+                                break;
+                            }
+                        }
+                        _ = fallback.tick() => {}
+                    }
+
+                    if let Ok(mut state) = state_clone.lock() {
+                        let _ = state.load_data(data_dir_clone.clone());
+                    }
+                }
+            });
+        } else {
+            tokio::spawn(async move {
+                let mut interval = interval(Duration::from_secs(5));
+                loop {
+                    interval.tick().await;
+
+                    if let Ok(mut state) = state_clone.lock() {
+                        // Pick up newly appended entries every 5 seconds
+                        let _ = state.load_data(data_dir_clone.clone());
+                    }
+                }
+            });
+        }
 
         let mut tick_interval = interval(Duration::from_millis(100));
 
@@ -395,6 +654,9 @@ impl App {
                 Some(PopupType::LifetimeStats) => {
                     LifetimePopupWidget::render(frame, area, &state);
                 }
+                Some(PopupType::LoadReport) => {
+                    LoadReportPopupWidget::render(frame, area, &state);
+                }
                 None => {}
             }
         }
@@ -434,6 +696,17 @@ impl App {
                                 };
                         }
                     }
+                    KeyCode::Char('l') => {
+                        // Toggle load report popup
+                        if let Ok(mut state) = self.state.lock() {
+                            state.active_popup =
+                                if state.active_popup == Some(PopupType::LoadReport) {
+                                    None
+                                } else {
+                                    Some(PopupType::LoadReport)
+                                };
+                        }
+                    }
                     KeyCode::Esc => {
                         // Close any popup if open
                         if let Ok(mut state) = self.state.lock() {
@@ -456,31 +729,105 @@ async fn main() -> Result<()> {
         plan: "pro".to_string(),
     });
 
+    let plan_registry = load_plan_registry(args.plans.as_deref())?;
+
     // If plan was specified via command line, use it and save it
-    let plan_str =
-        if args.plan != "pro" || std::env::args().any(|arg| arg == "--plan" || arg == "-p") {
-            // Plan was explicitly specified, update config
-            config.plan = args.plan.clone();
-            if let Err(e) = save_config(&config) {
-                eprintln!("Warning: Could not save config: {}", e);
-            }
-            args.plan.as_str()
-        } else {
-            // Use plan from config
-            config.plan.as_str()
-        };
+    let plan = if args.plan != "pro" || std::env::args().any(|arg| arg == "--plan" || arg == "-p") {
+        let plan = plan_registry.plan(&args.plan)?;
+        // Plan was explicitly specified, update config
+        config.plan = args.plan.clone();
+        if let Err(e) = save_config(&config) {
+            eprintln!("Warning: Could not save config: {}", e);
+        }
+        plan
+    } else {
+        // Use plan from config
+        plan_registry
+            .plan(&config.plan)
+            .with_context(|| format!("Invalid plan in config: {}", config.plan))?
+    };
 
-    let plan = match plan_str {
-        "pro" => ClaudePlan::Pro,
-        "max5" => ClaudePlan::Max5,
-        "max20" => ClaudePlan::Max20,
-        _ => ClaudePlan::Pro,
+    let jobs = args.jobs.unwrap_or_else(DataLoader::available_workers);
+    let mut pricing = if args.pricing.is_some() || args.catalog.is_some() {
+        PricingProvider::load(args.catalog.as_deref(), args.pricing.as_deref())?
+    } else {
+        PricingProvider::load_default().unwrap_or_else(|e| {
+            eprintln!("Warning: Could not load pricing file: {}", e);
+            PricingProvider::new()
+        })
     };
 
+    if let Some(profile) = &args.pricing_profile {
+        pricing.set_default_profile(profile)?;
+    }
+
+    let mut root_profiles = Vec::new();
+    for root_profile in &args.root_profiles {
+        let (root, profile) = root_profile.split_once('=').ok_or_else(|| {
+            anyhow::anyhow!(
+                "Expected PATH=PROFILE for --root-profile, got '{}'",
+                root_profile
+            )
+        })?;
+        if !pricing.has_profile(profile) {
+            return Err(anyhow::anyhow!(
+                "Unknown pricing profile '{}' (expected one of: {})",
+                profile,
+                pricing.profile_names().join(", ")
+            ));
+        }
+        root_profiles.push((PathBuf::from(root), profile.to_string()));
+    }
+
+    if !(args.session_hours.is_finite() && args.session_hours > 0.0) {
+        return Err(anyhow::anyhow!(
+            "--session-hours must be positive, got {}",
+            args.session_hours
+        ));
+    }
+    let session_config = SessionConfig::new(
+        chrono::Duration::minutes((args.session_hours * 60.0).round() as i64),
+        args.session_rounding,
+    );
+
+    let mut weekly_config = WeeklyConfig::new().with_reset(args.weekly_reset);
+    if let Some(limit) = args.weekly_limit.or(plan.weekly_limit()) {
+        weekly_config = weekly_config.with_all_models_budget(limit);
+    }
+    if let Some(limit) = args.weekly_opus_limit {
+        weekly_config = weekly_config.with_opus_budget(limit);
+    }
+
+    let currency = load_currency(&args.currency, args.rates.as_deref())?;
+
+    let mut usage_monitor = UsageMonitor::new()
+        .with_load_workers(jobs)
+        .with_pricing(pricing)
+        .with_cost_mode(args.cost_mode)
+        .with_session_config(session_config)
+        .with_weekly_config(weekly_config)
+        .with_limit_model(load_limit_model(
+            &args.limit_model,
+            args.limit_config.as_deref(),
+        )?);
+
+    for (root, profile) in &root_profiles {
+        usage_monitor = usage_monitor.with_root_profile(root, profile);
+    }
+
+    if !args.no_archive {
+        if let Some(archive_path) = UsageArchive::default_path() {
+            match UsageArchive::open(&archive_path) {
+                Ok(archive) => usage_monitor = usage_monitor.with_archive(archive),
+                Err(e) => eprintln!("Warning: Could not open usage archive: {}", e),
+            }
+        }
+    }
+
     let mut terminal = ratatui::init();
-    let mut app = App::new(plan, args.data_dir.clone());
+    let mut app = App::new(plan, args.data_dir.clone(), usage_monitor, currency);
 
-    let result = app.run(&mut terminal, args.data_dir).await;
+    let result = app.run(&mut terminal, args.data_dir, args.watch).await;
 
     ratatui::restore();
 
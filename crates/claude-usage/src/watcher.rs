@@ -0,0 +1,121 @@
+//! Filesystem watcher that triggers a reload when a transcript changes
+
+use anyhow::Result;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+/// How long to let a burst of writes settle before reloading.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+pub struct DataWatcher {
+    // Dropping the watcher stops the notifications, so it is kept alive here.
+    _watcher: RecommendedWatcher,
+}
+
+impl DataWatcher {
+    /// Watches `paths` recursively and sends on `tx` whenever a `.jsonl` file
+    /// is created or modified beneath them.
+    pub fn spawn(paths: &[PathBuf], tx: UnboundedSender<()>) -> Result<Self> {
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            if let Ok(event) = res {
+                if is_transcript_change(&event) {
+                    let _ = tx.send(());
+                }
+            }
+        })?;
+
+        for path in paths {
+            watcher.watch(path, RecursiveMode::Recursive)?;
+        }
+
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+/// Waits for the next change notification and swallows the rest of its
+/// burst, so one reload covers them all. Returns `None` once the watcher is
+/// gone.
+pub async fn next_reload(rx: &mut UnboundedReceiver<()>) -> Option<()> {
+    rx.recv().await?;
+    tokio::time::sleep(DEBOUNCE).await;
+    while rx.try_recv().is_ok() {}
+    Some(())
+}
+
+fn is_transcript_change(event: &Event) -> bool {
+    matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+        && event
+            .paths
+            .iter()
+            .any(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+}
+
+/// Directories to watch for a `--data-dir` value or the discovered defaults.
+pub fn watch_paths(data_dir: Option<&str>, discovered: Vec<PathBuf>) -> Vec<PathBuf> {
+    match data_dir {
+        Some(data_path) => vec![Path::new(data_path).to_path_buf()],
+        None => discovered,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_burst_of_changes_triggers_one_reload() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        tx.send(()).unwrap();
+        tx.send(()).unwrap();
+
+        // Changes arriving while the burst settles are folded in too
+        let late = tx.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(DEBOUNCE / 4).await;
+            late.send(()).unwrap();
+        });
+
+        assert_eq!(next_reload(&mut rx).await, Some(()));
+        assert!(rx.try_recv().is_err());
+
+        drop(tx);
+        assert_eq!(next_reload(&mut rx).await, None);
+    }
+
+    // Depends on the platform delivering real notifications in time, which
+    // inotify-limited or overlay filesystems may not
+    #[tokio::test]
+    #[ignore]
+    async fn test_appended_transcript_triggers_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let transcript = dir.path().join("session.jsonl");
+        std::fs::write(&transcript, "").unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let _watcher = DataWatcher::spawn(&[dir.path().to_path_buf()], tx).unwrap();
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&transcript)
+            .unwrap();
+        writeln!(file, "{{}}").unwrap();
+        file.sync_all().unwrap();
+
+        let reload = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv()).await;
+        assert_eq!(reload.unwrap(), Some(()));
+    }
+
+    #[test]
+    fn test_ignores_non_transcript_changes() {
+        let event = Event::new(EventKind::Modify(notify::event::ModifyKind::Any))
+            .add_path(PathBuf::from("/tmp/notes.txt"));
+        assert!(!is_transcript_change(&event));
+
+        let event = event.add_path(PathBuf::from("/tmp/session.jsonl"));
+        assert!(is_transcript_change(&event));
+    }
+}
@@ -33,9 +33,10 @@ impl LifetimePopupWidget {
 
     fn create_lifetime_stats_text(state: &AppState) -> Vec<Line> {
         let lifetime_tokens = state.get_lifetime_tokens();
-        let lifetime_percentage = state.get_lifetime_percentage(state.plan);
-        let total_cost = state.get_total_cost();
+        let lifetime_percentage = state.get_lifetime_percentage(&state.plan);
+        let total_cost = state.format_total_cost();
         let blocks_count = state.get_session_blocks_count();
+        let duplicates_count = state.usage_monitor.duplicate_count();
         let avg_burn_rate = state.get_average_burn_rate();
         let peak_burn_rate = state.get_peak_burn_rate();
 
@@ -67,7 +68,7 @@ impl LifetimePopupWidget {
             Line::from(vec![
                 Span::styled("Total Cost: ", Style::default().fg(Color::White)),
                 Span::styled(
-                    format!("${:.3}", total_cost),
+                    total_cost,
                     Style::default()
                         .fg(Color::Green)
                         .add_modifier(Modifier::BOLD),
@@ -82,8 +83,25 @@ impl LifetimePopupWidget {
                         .add_modifier(Modifier::BOLD),
                 ),
             ]),
+            Line::from(vec![
+                Span::styled("Duplicates Skipped: ", Style::default().fg(Color::White)),
+                Span::styled(
+                    format!("{}", duplicates_count),
+                    Style::default().fg(Color::Gray),
+                ),
+            ]),
         ];
 
+        if let Some(archive) = state.usage_monitor.archive() {
+            lifetime_text.push(Line::from(vec![
+                Span::styled("Archived Entries: ", Style::default().fg(Color::White)),
+                Span::styled(
+                    format!("{}", archive.len()),
+                    Style::default().fg(Color::Gray),
+                ),
+            ]));
+        }
+
         // Add burn rate information
         if let Some(avg_br) = avg_burn_rate {
             lifetime_text.push(Line::from(vec![
@@ -155,6 +173,57 @@ impl LifetimePopupWidget {
             ]));
         }
 
+        let limit_hits = state.usage_monitor.get_limit_hits();
+        if !limit_hits.is_empty() {
+            lifetime_text.extend(vec![
+                Line::from(" "),
+                Line::from(vec![Span::styled(
+                    format!("Limit Hits ({}):", limit_hits.len()),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                )]),
+            ]);
+
+            for (event, units) in limit_hits.iter().rev().take(5) {
+                lifetime_text.push(Line::from(vec![
+                    Span::styled("  ", Style::default()),
+                    Span::styled(
+                        format!(
+                            "{} at {} tokens",
+                            event.timestamp().format("%Y-%m-%d %H:%M"),
+                            *units as u64
+                        ),
+                        Style::default().fg(Color::White),
+                    ),
+                ]));
+            }
+        }
+
+        let profile_breakdown = state.usage_monitor.get_profile_breakdown();
+        if !profile_breakdown.is_empty() {
+            lifetime_text.extend(vec![
+                Line::from(" "),
+                Line::from(vec![Span::styled(
+                    "Cost by Pricing Profile:",
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )]),
+            ]);
+
+            let mut sorted_profiles: Vec<_> = profile_breakdown.iter().collect();
+            sorted_profiles.sort_by(|a, b| a.0.cmp(b.0));
+
+            for (profile, cost) in sorted_profiles {
+                lifetime_text.push(Line::from(vec![
+                    Span::styled("  ", Style::default()),
+                    Span::styled(
+                        format!("{}: {}", profile, state.format_profile_cost(profile, *cost)),
+                        Style::default().fg(Color::White),
+                    ),
+                ]));
+            }
+        }
+
         lifetime_text.extend(vec![
             Line::from(" "),
             Line::from(vec![
@@ -0,0 +1,239 @@
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::AppState;
+
+pub struct LoadReportPopupWidget;
+
+impl LoadReportPopupWidget {
+    pub fn render(frame: &mut Frame, area: Rect, state: &AppState) {
+        let popup_area = Self::centered_rect(70, 80, area);
+
+        // Clear the area first
+        frame.render_widget(Clear, popup_area);
+
+        let report_text = Self::create_load_report_text(state);
+
+        let popup = Paragraph::new(report_text)
+            .block(
+                Block::bordered()
+                    .title("Load Report")
+                    .title_alignment(Alignment::Center)
+                    .style(Style::default().fg(Color::Cyan)),
+            )
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: false });
+
+        frame.render_widget(popup, popup_area);
+    }
+
+    fn create_load_report_text(state: &AppState) -> Vec<Line> {
+        let report = state.usage_monitor.load_report();
+
+        let mut report_text = vec![
+            Line::from(vec![
+                Span::styled("Files Scanned: ", Style::default().fg(Color::White)),
+                Span::styled(
+                    format!("{}", report.files_scanned()),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Lines Read: ", Style::default().fg(Color::White)),
+                Span::styled(
+                    format!("{}", report.lines_read()),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Usage Entries: ", Style::default().fg(Color::White)),
+                Span::styled(
+                    format!("{}", state.usage_monitor.entry_count()),
+                    Style::default()
+                        .fg(Color::Green)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Duplicates Skipped: ", Style::default().fg(Color::White)),
+                Span::styled(
+                    format!("{}", report.duplicates_removed()),
+                    Style::default().fg(Color::Gray),
+                ),
+            ]),
+            Line::from(" "),
+            Line::from(vec![Span::styled(
+                format!("Skipped Lines ({}):", report.total_skipped()),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )]),
+        ];
+
+        for (reason, count) in report.skipped_lines() {
+            report_text.push(Line::from(vec![
+                Span::styled("  ", Style::default()),
+                Span::styled(
+                    format!("{}: {}", reason.description(), count),
+                    Style::default().fg(Color::White),
+                ),
+            ]));
+        }
+
+        if !report.unknown_models().is_empty() {
+            report_text.extend(vec![
+                Line::from(" "),
+                Line::from(vec![Span::styled(
+                    "Unknown Models (no pricing entry):",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                )]),
+            ]);
+
+            for model in report.unknown_models() {
+                report_text.push(Line::from(vec![
+                    Span::styled("  ", Style::default()),
+                    Span::styled(model.clone(), Style::default().fg(Color::White)),
+                ]));
+            }
+
+            if report.unpriced_entries() > 0 {
+                report_text.push(Line::from(vec![
+                    Span::styled("  ", Style::default()),
+                    Span::styled(
+                        format!(
+                            "{} entries without a logged cost counted as $0",
+                            report.unpriced_entries()
+                        ),
+                        Style::default().fg(Color::Gray),
+                    ),
+                ]));
+            }
+        }
+
+        if report.costs_audited() > 0 {
+            report_text.extend(vec![
+                Line::from(" "),
+                Line::from(vec![Span::styled(
+                    format!(
+                        "Cost Audit: {} of {} entries differ",
+                        report.cost_discrepancies().len(),
+                        report.costs_audited()
+                    ),
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )]),
+            ]);
+
+            let mut discrepancies: Vec<_> = report.cost_discrepancies().iter().collect();
+            discrepancies.sort_by_key(|discrepancy| {
+                std::cmp::Reverse(discrepancy.difference().micros().abs())
+            });
+
+            for discrepancy in discrepancies.into_iter().take(10) {
+                report_text.push(Line::from(vec![
+                    Span::styled("  ", Style::default()),
+                    Span::styled(
+                        format!(
+                            "{} {}: logged ${:.4}, calculated ${:.4}",
+                            discrepancy.timestamp().format("%Y-%m-%d %H:%M"),
+                            discrepancy.model(),
+                            discrepancy.logged_cost_usd(),
+                            discrepancy.calculated_cost_usd()
+                        ),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                ]));
+            }
+        }
+
+        if !report.resolved_models().is_empty() {
+            report_text.extend(vec![
+                Line::from(" "),
+                Line::from(vec![Span::styled(
+                    "Resolved Models:",
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )]),
+            ]);
+
+            for (model, (resolved, rule)) in report.resolved_models() {
+                report_text.push(Line::from(vec![
+                    Span::styled("  ", Style::default()),
+                    Span::styled(
+                        format!("{} -> {} ({})", model, resolved, rule.description()),
+                        Style::default().fg(Color::White),
+                    ),
+                ]));
+            }
+        }
+
+        if !report.unreadable_files().is_empty() {
+            report_text.extend(vec![
+                Line::from(" "),
+                Line::from(vec![Span::styled(
+                    "Unreadable Files:",
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                )]),
+            ]);
+
+            for (path, error) in report.unreadable_files() {
+                report_text.push(Line::from(vec![
+                    Span::styled("  ", Style::default()),
+                    Span::styled(
+                        format!("{}: {}", path.display(), error),
+                        Style::default().fg(Color::Red),
+                    ),
+                ]));
+            }
+        }
+
+        report_text.extend(vec![
+            Line::from(" "),
+            Line::from(vec![
+                Span::styled("Press ", Style::default().fg(Color::Gray)),
+                Span::styled(
+                    "l",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(" to close", Style::default().fg(Color::Gray)),
+            ]),
+        ]);
+
+        report_text
+    }
+
+    fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+        let popup_layout = ratatui::layout::Layout::default()
+            .direction(ratatui::layout::Direction::Vertical)
+            .constraints([
+                ratatui::layout::Constraint::Percentage((100 - percent_y) / 2),
+                ratatui::layout::Constraint::Percentage(percent_y),
+                ratatui::layout::Constraint::Percentage((100 - percent_y) / 2),
+            ])
+            .split(r);
+
+        ratatui::layout::Layout::default()
+            .direction(ratatui::layout::Direction::Horizontal)
+            .constraints([
+                ratatui::layout::Constraint::Percentage((100 - percent_x) / 2),
+                ratatui::layout::Constraint::Percentage(percent_x),
+                ratatui::layout::Constraint::Percentage((100 - percent_x) / 2),
+            ])
+            .split(popup_layout[1])[1]
+    }
+}
@@ -2,6 +2,7 @@
 
 pub mod header;
 pub mod lifetime_popup;
+pub mod load_report_popup;
 pub mod popup;
 pub mod predictions;
 pub mod progress_bars;
@@ -10,6 +11,7 @@ pub mod statistics;
 
 pub use header::*;
 pub use lifetime_popup::*;
+pub use load_report_popup::*;
 pub use popup::*;
 pub use predictions::*;
 pub use progress_bars::*;
@@ -33,7 +33,7 @@ impl PopupWidget {
 
     fn create_debug_breakdown_text(state: &AppState) -> Vec<Line> {
         let current_tokens = state.get_current_tokens();
-        let current_cost = state.get_current_block_cost();
+        let current_cost = state.format_current_block_cost();
         let current_duration = state.get_current_block_duration();
 
         let mut debug_text = vec![
@@ -49,7 +49,7 @@ impl PopupWidget {
             Line::from(vec![
                 Span::styled("Block Cost: ", Style::default().fg(Color::White)),
                 Span::styled(
-                    format!("${:.3}", current_cost),
+                    current_cost,
                     Style::default()
                         .fg(Color::Green)
                         .add_modifier(Modifier::BOLD),
@@ -111,6 +111,28 @@ impl PopupWidget {
             ]));
         }
 
+        if let Some((event, units)) = state.get_current_block_limit_hit() {
+            let resets = event
+                .resets_at()
+                .map(|resets_at| format!(", resets {}", resets_at.format("%H:%M")))
+                .unwrap_or_default();
+            debug_text.extend(vec![
+                Line::from(" "),
+                Line::from(vec![
+                    Span::styled("Limit Hit: ", Style::default().fg(Color::White)),
+                    Span::styled(
+                        format!(
+                            "{} at {} tokens{}",
+                            event.timestamp().format("%H:%M"),
+                            units as u64,
+                            resets
+                        ),
+                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    ),
+                ]),
+            ]);
+        }
+
         debug_text.extend(vec![
             Line::from(" "),
             Line::from(vec![
@@ -16,7 +16,7 @@ impl PredictionsWidget {
         let current_tokens = state.get_current_tokens();
 
         let predictions_text = if let Some(br) = burn_rate {
-            let remaining_tokens = state.plan.max_tokens().saturating_sub(current_tokens);
+            let remaining_tokens = state.get_token_limit().saturating_sub(current_tokens);
             let minutes_to_limit = if br.tokens_per_minute() > 0.0 {
                 remaining_tokens as f64 / br.tokens_per_minute()
             } else {
@@ -99,6 +99,10 @@ impl PredictionsWidget {
                 "  ~/.config/claude/projects",
                 Style::default().fg(Color::Gray),
             )]));
+            no_data_text.push(Line::from(vec![Span::styled(
+                "  directories listed in CLAUDE_CONFIG_DIR",
+                Style::default().fg(Color::Gray),
+            )]));
             no_data_text.push(Line::from(" "));
         }
 
@@ -13,7 +13,11 @@ impl ProgressBarsWidget {
     pub fn render(frame: &mut Frame, area: Rect, state: &AppState) {
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .constraints([
+                Constraint::Percentage(34),
+                Constraint::Percentage(33),
+                Constraint::Percentage(33),
+            ])
             .split(area);
 
         let usage_percentage = state.get_usage_percentage();
@@ -34,11 +38,54 @@ impl ProgressBarsWidget {
         let (time_remaining, time_percentage) = state.get_time_to_reset_formatted();
         let elapsed_percentage = (1.0 - time_percentage) * 100.0;
         let time_gauge = Gauge::default()
-            .block(Block::bordered().title("Session Time (5h blocks)"))
+            .block(Block::bordered().title(format!(
+                "Session Time ({} blocks)",
+                state.get_session_length_formatted()
+            )))
             .gauge_style(Style::default().fg(Color::Blue))
-            .percent(elapsed_percentage.max(0.0).min(100.0) as u16)
+            .percent(elapsed_percentage.clamp(0.0, 100.0) as u16)
             .label(format!("{} remaining", time_remaining));
 
         frame.render_widget(time_gauge, chunks[1]);
+
+        let weekly = state.get_weekly_usage();
+        let all_models = weekly.all_models();
+        // Colour by whichever budget is closer to running out
+        let weekly_percentage = all_models
+            .percentage()
+            .unwrap_or(0.0)
+            .max(weekly.opus().percentage().unwrap_or(0.0));
+
+        let mut weekly_label = match all_models.percentage() {
+            Some(percentage) => format!("{:.1}%", percentage),
+            None => format!("{:.0} units", all_models.used()),
+        };
+        if let Some(opus_percentage) = weekly.opus().percentage() {
+            weekly_label.push_str(&format!(" (Opus {:.1}%)", opus_percentage));
+        }
+        match weekly.projected_exhaustion() {
+            Some(exhaustion) => weekly_label.push_str(&format!(
+                ", limit in {}",
+                state.format_time_until(exhaustion)
+            )),
+            None => weekly_label.push_str(&format!(
+                ", resets in {}",
+                state.format_time_until(weekly.resets_at())
+            )),
+        }
+
+        let weekly_gauge = Gauge::default()
+            .block(Block::bordered().title("Weekly Usage"))
+            .gauge_style(if weekly_percentage > 80.0 {
+                Style::default().fg(Color::Red)
+            } else if weekly_percentage > 60.0 {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::Green)
+            })
+            .percent(weekly_percentage.clamp(0.0, 100.0) as u16)
+            .label(weekly_label);
+
+        frame.render_widget(weekly_gauge, chunks[2]);
     }
 }
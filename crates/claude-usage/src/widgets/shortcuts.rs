@@ -41,7 +41,14 @@ impl ShortcutsWidget {
                     .fg(Color::Yellow)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" for session stats", Style::default().fg(Color::Gray)),
+            Span::styled(" for session stats, ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                "l",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" for load report", Style::default().fg(Color::Gray)),
         ])];
 
         let shortcuts = Paragraph::new(shortcuts_text).alignment(Alignment::Center);
@@ -6,7 +6,7 @@ use ratatui::{
     Frame,
 };
 
-use crate::AppState;
+use crate::{AppState, RefreshMode};
 
 pub struct StatisticsWidget;
 
@@ -50,7 +50,19 @@ impl StatisticsWidget {
                         .add_modifier(Modifier::BOLD),
                 ),
                 Span::styled(
-                    format!(" / {}", state.plan.max_tokens()),
+                    match state.get_limit_estimate_label() {
+                        Some(label) => format!(
+                            " / {} ({}, {})",
+                            state.get_token_limit(),
+                            state.usage_monitor.limit_model().name(),
+                            label
+                        ),
+                        None => format!(
+                            " / {} ({})",
+                            state.get_token_limit(),
+                            state.usage_monitor.limit_model().name()
+                        ),
+                    },
                     Style::default().fg(Color::Gray),
                 ),
             ]),
@@ -85,6 +97,19 @@ impl StatisticsWidget {
                     state.last_update.format("%H:%M:%S UTC").to_string(),
                     Style::default().fg(Color::Cyan),
                 ),
+                Span::styled(
+                    match (state.refresh_mode, &state.watch_error) {
+                        (RefreshMode::Watching, _) => {
+                            " (watching for changes, polling every 60s)".to_string()
+                        }
+                        (RefreshMode::Polling, Some(error)) => format!(
+                            " (polling every 5s, watch failed: {})",
+                            error.chars().take(40).collect::<String>()
+                        ),
+                        (RefreshMode::Polling, None) => " (polling every 5s)".to_string(),
+                    },
+                    Style::default().fg(Color::Gray),
+                ),
             ]));
         }
 